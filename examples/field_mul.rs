@@ -0,0 +1,65 @@
+//! Times field multiplication and inversion in the secp256k1 base field with the BigUint-backed
+//! FieldElement and the fixed-width Fe256, and the per-element cost of batch inversion.
+//!
+//! Run with `cargo run --release --example field_mul`
+use bitcoin::fe256::Fe256;
+use bitcoin::field::Field;
+use bitcoin::finite_field::FieldElement;
+use bitcoin::hash::sha256;
+use num_bigint::BigUint;
+use std::time::{Duration, Instant};
+
+const ROUNDS: u32 = 10_000;
+
+// Times repeated squaring, inversion, then batch inversion of the same starting value
+fn time<F: Field>(start: F) -> (Duration, Duration, Duration) {
+    let mut x = start.clone();
+    let begin = Instant::now();
+    for _ in 0..ROUNDS {
+        x = x.squared();
+    }
+    let multiply = begin.elapsed() / ROUNDS;
+    let begin = Instant::now();
+    for _ in 0..ROUNDS / 100 {
+        x = x.invert().unwrap();
+    }
+    let invert = begin.elapsed() / (ROUNDS / 100);
+    let mut batch: Vec<F> = (0..ROUNDS / 10)
+        .map(|_| {
+            x = x.squared();
+            x.clone()
+        })
+        .collect();
+    let begin = Instant::now();
+    F::batch_invert(&mut batch);
+    (multiply, invert, begin.elapsed() / (ROUNDS / 10))
+}
+
+fn main() {
+    let p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    let x = BigUint::from_bytes_be(&sha256(b"field_mul")) % &p;
+
+    let (multiply, invert, batch) = time(FieldElement::new(x.clone(), p.clone()));
+    println!(
+        "FieldElement: {:?} per square, {:?} per inversion, {:?} per batch inversion",
+        multiply, invert, batch
+    );
+    let element = FieldElement::new(x.clone(), p.clone());
+    let begin = Instant::now();
+    for _ in 0..ROUNDS / 100 {
+        element.invert_constant_time().unwrap();
+    }
+    println!(
+        "FieldElement: {:?} per constant-time inversion",
+        begin.elapsed() / (ROUNDS / 100)
+    );
+    let (multiply, invert, batch) = time(Fe256::from_biguint(&x));
+    println!(
+        "Fe256:        {:?} per square, {:?} per inversion, {:?} per batch inversion",
+        multiply, invert, batch
+    );
+}
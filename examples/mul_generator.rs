@@ -0,0 +1,38 @@
+//! Compares secp256k1 kG through the generator table against the Montgomery ladder behind the
+//! `*` operator. The first mul_generator call builds the table, so it is timed separately.
+//!
+//! Run with `cargo run --release --example mul_generator`
+use bitcoin::curves::secp256k1;
+use bitcoin::hash::sha256;
+use bitcoin::scalar::Scalar;
+use std::time::Instant;
+
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let secp = secp256k1();
+    let generator = secp.get_generator();
+    let scalars: Vec<Scalar> = (0..ROUNDS)
+        .map(|i| Scalar::from_bytes_reduced(&sha256(&i.to_be_bytes()), secp.get_order()))
+        .collect();
+
+    let start = Instant::now();
+    secp.mul_generator(&Scalar::new(1, secp.get_order()));
+    println!("table build:   {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let ladder: Vec<_> = scalars.iter().map(|k| &generator * k).collect();
+    let ladder_time = start.elapsed() / ROUNDS;
+
+    let start = Instant::now();
+    let table: Vec<_> = scalars.iter().map(|k| secp.mul_generator(k)).collect();
+    let table_time = start.elapsed() / ROUNDS;
+
+    assert_eq!(ladder, table);
+    println!("ladder:        {:?} per multiplication", ladder_time);
+    println!("mul_generator: {:?} per multiplication", table_time);
+    println!(
+        "speedup:       {:.1}x",
+        ladder_time.as_secs_f64() / table_time.as_secs_f64()
+    );
+}
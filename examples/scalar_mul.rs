@@ -0,0 +1,70 @@
+//! Times secp256k1 G * n with the affine double-and-add that pays for a field inversion on every
+//! addition and doubling, the variable-time path used for verification, and the constant-time
+//! Montgomery ladder behind the `*` operator. A pseudorandom scalar is timed as well, since the
+//! endomorphism splits n - 1 = -1 (mod n) into trivially short halves.
+//!
+//! Run with `cargo run --release --example scalar_mul`
+use bitcoin::curves::secp256k1;
+use bitcoin::elliptic_curve::CurvePoint;
+use bitcoin::hash::sha256;
+use bitcoin::scalar::Scalar;
+use num_bigint::{BigUint, ToBigUint};
+use std::time::Instant;
+
+fn affine_multiply(point: &CurvePoint, scalar: &BigUint) -> CurvePoint {
+    let zero = 0.to_biguint().unwrap();
+    let one = 1.to_biguint().unwrap();
+    let mut rhs = scalar.clone();
+    let mut current = point.clone();
+    let mut result = CurvePoint::infinity(point.get_curve());
+    while rhs != zero {
+        if &rhs & &one != zero {
+            result = &result + &current;
+        }
+        current = &current + &current;
+        rhs >>= 1;
+    }
+    result
+}
+
+fn main() {
+    let secp = secp256k1();
+    let generator = secp.get_generator();
+    let order = secp.get_order();
+
+    let start = Instant::now();
+    let affine = affine_multiply(&generator, order);
+    let affine_time = start.elapsed();
+
+    // n - 1 rather than n, since a Scalar is always reduced below the group order
+    let start = Instant::now();
+    let vartime = generator.mul_vartime(&-Scalar::new(1, order)) + generator.clone();
+    let vartime_time = start.elapsed();
+
+    let start = Instant::now();
+    let ladder = &generator * order;
+    let ladder_time = start.elapsed();
+
+    assert!(affine.is_infinity() && vartime.is_infinity() && ladder.is_infinity());
+    println!("G * n");
+    println!("  affine:  {:?}", affine_time);
+    println!("  vartime: {:?}", vartime_time);
+    println!("  ladder:  {:?}", ladder_time);
+
+    let k = Scalar::from_bytes_reduced(&sha256(b"scalar_mul"), order);
+    let start = Instant::now();
+    let affine = affine_multiply(&generator, k.get_value());
+    let affine_time = start.elapsed();
+    let start = Instant::now();
+    let vartime = generator.mul_vartime(&k);
+    let vartime_time = start.elapsed();
+    let start = Instant::now();
+    let ladder = &generator * &k;
+    let ladder_time = start.elapsed();
+
+    assert!(affine == vartime && vartime == ladder);
+    println!("G * k");
+    println!("  affine:  {:?}", affine_time);
+    println!("  vartime: {:?}", vartime_time);
+    println!("  ladder:  {:?}", ladder_time);
+}
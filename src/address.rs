@@ -0,0 +1,85 @@
+use crate::ecdsa::PublicKey;
+use crate::encoding::{
+    base58check_decode, base58check_encode, bech32_decode, bech32_encode, convert_bits,
+};
+use crate::error::Error;
+use crate::field::Field;
+use crate::hash::hash160;
+use std::fmt;
+use std::str::FromStr;
+
+// Mainnet version byte of P2PKH addresses and human-readable part of segwit addresses
+const P2PKH_VERSION: u8 = 0x00;
+const SEGWIT_HRP: &str = "bc";
+
+/// A mainnet address paying to the HASH160 of a single public key: either legacy pay-to-pubkey-hash
+/// (base58check, starting with 1) or native segwit v0 pay-to-witness-pubkey-hash (bech32,
+/// starting with bc1q).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    P2pkh([u8; 20]),
+    P2wpkh([u8; 20]),
+}
+
+impl Address {
+    /// Returns the P2PKH address of a key. The compressed and uncompressed SEC encodings hash to
+    /// different addresses, so the choice has to match the one used when the address was made.
+    pub fn p2pkh<F: Field>(key: &PublicKey<F>, compressed: bool) -> Address {
+        Address::P2pkh(hash160(&key.get_point().to_sec(compressed)))
+    }
+    /// Returns the P2WPKH address of a key. Segwit only allows compressed keys.
+    pub fn p2wpkh<F: Field>(key: &PublicKey<F>) -> Address {
+        Address::P2wpkh(hash160(&key.get_point().to_sec(true)))
+    }
+    /// Returns the HASH160 of the public key the address pays to
+    pub fn get_hash(&self) -> &[u8; 20] {
+        match self {
+            Address::P2pkh(hash) | Address::P2wpkh(hash) => hash,
+        }
+    }
+}
+
+impl FromStr for Address {
+    type Err = Error;
+    /// Parses a mainnet P2PKH or P2WPKH address. Other address types, such as P2SH, P2WSH and
+    /// taproot, and testnet addresses are rejected with InvalidAddress.
+    fn from_str(address: &str) -> Result<Address, Error> {
+        let mut hash = [0u8; 20];
+        if address.to_lowercase().starts_with(SEGWIT_HRP) {
+            let (hrp, data) = bech32_decode(address)?;
+            // A witness version 0 program of 20 bytes
+            if hrp != SEGWIT_HRP || data.first() != Some(&0) {
+                return Err(Error::InvalidAddress);
+            }
+            let program = convert_bits(&data[1..], 5, 8, false).ok_or(Error::InvalidAddress)?;
+            if program.len() != 20 {
+                return Err(Error::InvalidAddress);
+            }
+            hash.copy_from_slice(&program);
+            return Ok(Address::P2wpkh(hash));
+        }
+        let payload = base58check_decode(address)?;
+        if payload.len() != 21 || payload[0] != P2PKH_VERSION {
+            return Err(Error::InvalidAddress);
+        }
+        hash.copy_from_slice(&payload[1..]);
+        Ok(Address::P2pkh(hash))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::P2pkh(hash) => {
+                let mut payload = vec![P2PKH_VERSION];
+                payload.extend_from_slice(hash);
+                write!(f, "{}", base58check_encode(&payload))
+            }
+            Address::P2wpkh(hash) => {
+                let mut data = vec![0];
+                data.extend(convert_bits(hash, 8, 5, true).unwrap());
+                write!(f, "{}", bech32_encode(SEGWIT_HRP, &data))
+            }
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::elliptic_curve::{CurvePoint, EllipticCurve};
+use crate::scalar::Scalar;
+use num_bigint::BigUint;
+use std::sync::OnceLock;
+
+/// A standard curve with its group parameters: a generator G of a subgroup of prime order n, and
+/// the cofactor h = #E / n
+#[derive(Debug)]
+pub struct NamedCurve {
+    name: &'static str,
+    curve: EllipticCurve,
+}
+
+impl NamedCurve {
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+    pub fn get_curve(&self) -> &EllipticCurve {
+        &self.curve
+    }
+    pub fn get_generator(&self) -> CurvePoint {
+        self.curve.get_generator().unwrap()
+    }
+    /// Computes kG from the curve's precomputed table of multiples of G
+    pub fn mul_generator(&self, scalar: &Scalar) -> CurvePoint {
+        self.curve.mul_generator(scalar).unwrap()
+    }
+    pub fn get_order(&self) -> &BigUint {
+        self.curve.get_order().unwrap()
+    }
+    pub fn get_cofactor(&self) -> &BigUint {
+        self.curve.get_cofactor().unwrap()
+    }
+}
+
+/// The Koblitz curve y**2 = x**3 + 7 used by Bitcoin, as specified in SEC 2, section 2.4.1.
+/// The parameters are constructed on first use and shared for the life of the program.
+pub fn secp256k1() -> &'static NamedCurve {
+    static SECP256K1: OnceLock<NamedCurve> = OnceLock::new();
+    SECP256K1.get_or_init(|| {
+        let hex = |digits: &[u8]| BigUint::parse_bytes(digits, 16).unwrap();
+        NamedCurve {
+            name: "secp256k1",
+            curve: EllipticCurve::new_large(
+                b"0",
+                b"7",
+                b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+            )
+            .with_group(
+                hex(b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+                hex(b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+                hex(b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"),
+                1,
+            )
+            // beta is a cube root of unity mod p and lambda one mod n, so that
+            // (beta * x, y) = lambda * (x, y)
+            .with_endomorphism(
+                hex(b"7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee"),
+                hex(b"5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72"),
+            ),
+        }
+    })
+}
@@ -0,0 +1,339 @@
+use crate::elliptic_curve::{CurvePoint, EllipticCurve};
+use crate::encoding::{parse_hex, to_fixed_bytes};
+use crate::error::{DerError, Error};
+use crate::field::Field;
+use crate::finite_field::FieldElement;
+use crate::hash::MessageHash;
+use crate::rfc6979::NonceGenerator;
+use crate::scalar::Scalar;
+use num_bigint::{BigUint, ToBigUint};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    r: BigUint,
+    s: BigUint,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublicKey<F = FieldElement> {
+    point: CurvePoint<F>,
+}
+
+pub struct PrivateKey<F = FieldElement> {
+    secret: Scalar,
+    public_key: PublicKey<F>,
+}
+
+impl Signature {
+    /// Creates a signature from r and s. Panics if either is negative.
+    pub fn new<T, U>(r: T, s: U) -> Signature
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        Signature::try_new(r, s).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates a signature from r and s, failing if either is negative. Their ranges are only
+    /// checked when verifying.
+    pub fn try_new<T, U>(r: T, s: U) -> Result<Signature, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        Ok(Signature {
+            r: r.to_biguint().ok_or(Error::NegativeValue)?,
+            s: s.to_biguint().ok_or(Error::NegativeValue)?,
+        })
+    }
+    /// Parses r and s from big-endian hex strings
+    pub fn from_hex(r: &[u8], s: &[u8]) -> Result<Signature, Error> {
+        Ok(Signature {
+            r: parse_hex(r)?,
+            s: parse_hex(s)?,
+        })
+    }
+    /// Parses the 64-byte compact form: r and s as 32-byte big-endian integers
+    pub fn from_compact(bytes: &[u8]) -> Result<Signature, Error> {
+        if bytes.len() != 64 {
+            return Err(Error::InvalidLength {
+                expected: 64,
+                found: bytes.len(),
+            });
+        }
+        Ok(Signature {
+            r: BigUint::from_bytes_be(&bytes[..32]),
+            s: BigUint::from_bytes_be(&bytes[32..]),
+        })
+    }
+    /// Serializes to the 64-byte compact form. Fails if r or s does not fit in 32 bytes.
+    pub fn to_compact(&self) -> Result<[u8; 64], Error> {
+        let mut bytes = [0u8; 64];
+        bytes[..32].copy_from_slice(&to_fixed_bytes(&self.r, 32)?);
+        bytes[32..].copy_from_slice(&to_fixed_bytes(&self.s, 32)?);
+        Ok(bytes)
+    }
+    /// Serializes to DER: 0x30 [total length] 0x02 [length of r] [r] 0x02 [length of s] [s].
+    /// Fails if r or s does not fit in 32 bytes, since the single length bytes of BIP 66 can't
+    /// describe anything longer.
+    pub fn to_der(&self) -> Result<Vec<u8>, Error> {
+        let r = der_integer(&self.r)?;
+        let s = der_integer(&self.s)?;
+        let mut der = vec![0x30, (r.len() + s.len()) as u8];
+        der.extend_from_slice(&r);
+        der.extend_from_slice(&s);
+        Ok(der)
+    }
+    /// Parses a DER signature (without a trailing sighash byte), enforcing the strict encoding
+    /// rules of BIP 66: minimal length bytes and integers that are neither negative nor padded
+    /// with unnecessary zeros
+    pub fn from_der(der: &[u8]) -> Result<Signature, Error> {
+        let fail = |reason| Err(Error::InvalidDer(reason));
+        if der.len() < 8 || der.len() > 72 {
+            return fail(DerError::BadTotalLength);
+        }
+        if der[0] != 0x30 {
+            return fail(DerError::MissingSequence);
+        }
+        if der[1] as usize != der.len() - 2 {
+            return fail(DerError::BadLength);
+        }
+        // r starts at offset 4 and s starts two bytes after r ends
+        let r_len = der[3] as usize;
+        if 5 + r_len >= der.len() {
+            return fail(DerError::BadLength);
+        }
+        let s_len = der[5 + r_len] as usize;
+        if r_len + s_len + 6 != der.len() {
+            return fail(DerError::BadLength);
+        }
+        let r = parse_der_integer(der[2], &der[4..4 + r_len])?;
+        let s = parse_der_integer(der[4 + r_len], &der[6 + r_len..])?;
+        Ok(Signature { r, s })
+    }
+    pub fn get_r(&self) -> &BigUint {
+        &self.r
+    }
+    pub fn get_s(&self) -> &BigUint {
+        &self.s
+    }
+    /// Returns true if s is at most half the group order, as required by BIP 62 and BIP 146
+    pub fn is_low_s(&self, order: &BigUint) -> bool {
+        self.s <= order >> 1
+    }
+    /// Checks that r and s both lie in [1, N)
+    pub fn check_range(&self, order: &BigUint) -> Result<(), Error> {
+        let zero = 0.to_biguint().unwrap();
+        if self.r == zero || &self.r >= order || self.s == zero || &self.s >= order {
+            return Err(Error::ScalarOutOfRange);
+        }
+        Ok(())
+    }
+    /// Verifies this signature over z against the given public key. Malformed signatures and keys
+    /// are reported as errors, while well-formed signatures that don't match return Ok(false).
+    pub fn verify<F: Field>(&self, key: &PublicKey<F>, z: &MessageHash) -> Result<bool, Error> {
+        if key.point.is_infinity() {
+            return Err(Error::PointAtInfinity);
+        }
+        let order = key
+            .point
+            .get_curve()
+            .get_order()
+            .ok_or(Error::MissingGroup)?;
+        self.check_range(order)?;
+        Ok(key.point.verify_signature(z, self))
+    }
+    /// Replaces a high s with N - s. Both (r, s) and (r, N - s) are valid signatures for the same
+    /// message, so relay policy only accepts the low form to prevent third-party malleation.
+    pub fn normalize_s(&mut self, order: &BigUint) {
+        if !self.is_low_s(order) {
+            self.s = order - &self.s;
+        }
+    }
+}
+
+impl<F: Field> PublicKey<F> {
+    pub fn new(point: CurvePoint<F>) -> PublicKey<F> {
+        PublicKey { point }
+    }
+    pub fn get_point(&self) -> &CurvePoint<F> {
+        &self.point
+    }
+}
+
+impl<F: Field> PrivateKey<F> {
+    /// Creates a private key from a secret scalar in [1, N), deriving the public point secret * G.
+    /// Panics if the curve has no group parameters or the secret is out of range.
+    pub fn new<T>(secret: T, curve: &EllipticCurve<F>) -> PrivateKey<F>
+    where
+        T: ToBigUint,
+    {
+        PrivateKey::try_new(secret, curve).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_new<T>(secret: T, curve: &EllipticCurve<F>) -> Result<PrivateKey<F>, Error>
+    where
+        T: ToBigUint,
+    {
+        let order = curve.get_order().ok_or(Error::MissingGroup)?;
+        let secret = secret.to_biguint().ok_or(Error::NegativeValue)?;
+        if secret == 0.to_biguint().unwrap() || &secret >= order {
+            return Err(Error::ScalarOutOfRange);
+        }
+        let secret = Scalar::new(secret, order);
+        Ok(PrivateKey {
+            public_key: PublicKey::new(curve.mul_generator(&secret).unwrap()),
+            secret,
+        })
+    }
+    pub fn get_secret(&self) -> &Scalar {
+        &self.secret
+    }
+    pub fn get_public_key(&self) -> &PublicKey<F> {
+        &self.public_key
+    }
+    // The constructor guarantees the curve has group parameters
+    fn get_order(&self) -> &BigUint {
+        self.public_key.point.get_curve().get_order().unwrap()
+    }
+    /// Signs the message hash z, deriving the nonce deterministically from the secret and z (RFC 6979)
+    pub fn sign(&self, z: &MessageHash) -> Signature {
+        self.sign_recoverable(z).0
+    }
+    /// Signs like sign, and also returns the recovery id that recover_public_key needs to
+    /// reconstruct the public key from the signature
+    pub fn sign_recoverable(&self, z: &MessageHash) -> (Signature, u8) {
+        let order = self.get_order();
+        NonceGenerator::new(self.secret.get_value(), z.as_bytes(), order)
+            .find_map(|k| self.sign_with_nonce_recoverable(z, &Scalar::new(k, order)))
+            .unwrap()
+    }
+    /// Signs the message hash z using the nonce k, normalizing the result to low-S. Returns None if
+    /// k is zero or produces a degenerate signature (r = 0 or s = 0), in which case the caller
+    /// must pick another nonce. Panics if k is modulo a different order than the key's curve.
+    /// Never reuse k across messages: two signatures with the same nonce reveal the secret.
+    pub fn sign_with_nonce(&self, z: &MessageHash, k: &Scalar) -> Option<Signature> {
+        self.sign_with_nonce_recoverable(z, k)
+            .map(|(signature, _)| signature)
+    }
+    fn sign_with_nonce_recoverable(&self, z: &MessageHash, k: &Scalar) -> Option<(Signature, u8)> {
+        let n = self.get_order();
+        let z = Scalar::from_bytes_reduced(z.as_bytes(), n);
+        if k.is_zero() {
+            return None;
+        }
+        // r is the x-coordinate of R = kG, and s = (z + r * secret) / k (mod N)
+        let curve = self.public_key.point.get_curve();
+        let point = curve.mul_generator(k).unwrap();
+        let x = point.get_x().to_integer();
+        let r = Scalar::new(x.clone(), n);
+        if r.is_zero() {
+            return None;
+        }
+        // The recovery id records the parity of kG's y-coordinate, and whether reducing its
+        // x-coordinate mod N wrapped around
+        let mut recid = point.get_y().is_odd() as u8 | ((&x >= n) as u8) << 1;
+        let s = (z + &r * &self.secret) * k.invert_constant_time().unwrap();
+        if s.is_zero() {
+            return None;
+        }
+        let mut signature = Signature {
+            r: r.get_value().clone(),
+            s: s.get_value().clone(),
+        };
+        // Replacing s with N - s is the same as signing with -k, whose R has the opposite y
+        if !signature.is_low_s(n) {
+            signature.normalize_s(n);
+            recid ^= 1;
+        }
+        Some((signature, recid))
+    }
+}
+
+/// Recovers the signer's public key from a signature over z and its recovery id, as returned by
+/// PrivateKey::sign_recoverable. The id picks R, the point whose x-coordinate gave r: R's
+/// x-coordinate is r + (recid / 2) * N, and its y-coordinate is odd when recid is. Then s * R =
+/// z * G + r * Q gives Q = r^-1 * (s * R - z * G).
+#[allow(non_snake_case)]
+pub fn recover_public_key<F: Field>(
+    signature: &Signature,
+    recid: u8,
+    z: &MessageHash,
+    curve: &EllipticCurve<F>,
+) -> Result<PublicKey<F>, Error> {
+    let G = curve.get_generator().ok_or(Error::MissingGroup)?;
+    let n = curve.get_order().unwrap();
+    if recid > 3 {
+        return Err(Error::InvalidRecoveryId(recid));
+    }
+    signature.check_range(n)?;
+    // Lift the x-coordinate to a point by decompressing it, which also rejects x >= p
+    let x = &signature.r + n * BigUint::from(recid >> 1);
+    let mut sec = vec![0x02 | (recid & 1)];
+    sec.extend(to_fixed_bytes(&x, G.get_x().to_bytes().len()).map_err(|_| Error::PointNotOnCurve)?);
+    let R = CurvePoint::from_sec(&sec, curve)?;
+    if curve.get_cofactor() != Some(&BigUint::from(1u8)) && !(&R * n.clone()).is_infinity() {
+        return Err(Error::PointNotOnCurve);
+    }
+    let r_inv = Scalar::new(signature.r.clone(), n).invert().unwrap();
+    let z = Scalar::from_bytes_reduced(z.as_bytes(), n);
+    let u = -(&z * &r_inv);
+    let v = Scalar::new(signature.s.clone(), n) * r_inv;
+    let Q = curve.multi_scalar_mul(&[(&u, &G), (&v, &R)]);
+    if Q.is_infinity() {
+        return Err(Error::PointAtInfinity);
+    }
+    Ok(PublicKey::new(Q))
+}
+
+// Encodes an unsigned integer of at most 32 bytes as a DER integer, prepending a zero byte if the
+// high bit is set so that the value isn't read as negative
+fn der_integer(value: &BigUint) -> Result<Vec<u8>, Error> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > 32 {
+        return Err(Error::InvalidLength {
+            expected: 32,
+            found: bytes.len(),
+        });
+    }
+    let mut encoded = vec![0x02];
+    if bytes[0] & 0x80 != 0 {
+        encoded.push(bytes.len() as u8 + 1);
+        encoded.push(0x00);
+    } else {
+        encoded.push(bytes.len() as u8);
+    }
+    encoded.extend_from_slice(&bytes);
+    Ok(encoded)
+}
+
+fn parse_der_integer(tag: u8, bytes: &[u8]) -> Result<BigUint, Error> {
+    if tag != 0x02 {
+        return Err(Error::InvalidDer(DerError::MissingInteger));
+    }
+    if bytes.is_empty() {
+        return Err(Error::InvalidDer(DerError::EmptyInteger));
+    }
+    if bytes[0] & 0x80 != 0 {
+        return Err(Error::InvalidDer(DerError::NegativeInteger));
+    }
+    // A leading zero is only allowed when the next byte would otherwise set the sign bit
+    if bytes.len() > 1 && bytes[0] == 0x00 && bytes[1] & 0x80 == 0 {
+        return Err(Error::InvalidDer(DerError::ExcessPadding));
+    }
+    Ok(BigUint::from_bytes_be(bytes))
+}
+
+// Leave the secret out of debug output, so keys can't leak into logs or panic messages
+impl<F: fmt::Debug> fmt::Debug for PrivateKey<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrivateKey")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signature({:x}, {:x})", self.r, self.s)
+    }
+}
@@ -1,18 +1,88 @@
+use crate::ecdsa::Signature;
+use crate::encoding::parse_hex;
+use crate::error::Error;
+use crate::field::Field;
 use crate::finite_field::FieldElement;
-use num_bigint::{BigUint, ToBigUint};
+use crate::hash::MessageHash;
+use crate::scalar::Scalar;
+use num_bigint::{BigInt, BigUint, Sign, ToBigInt, ToBigUint};
+use num_integer::Integer;
+use std::cmp;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
+/// The curve y**2 = x**3 + ax + b over a field F. The default FieldElement works over any prime
+/// field, while fixed-width backends like Fe256 implement Field for faster arithmetic.
+///
+/// The parameters sit behind an Arc, so cloning a curve is cheap and every point keeps its own
+/// handle to its curve rather than borrowing it. Points can then be stored, returned from the
+/// function that built the curve, and sent across threads.
 #[derive(Debug, Clone)]
-pub struct EllipticCurve {
-    a: FieldElement,
-    b: FieldElement,
+pub struct EllipticCurve<F = FieldElement> {
+    parameters: Arc<Parameters<F>>,
 }
 
-#[derive(Debug)]
-pub struct CurvePoint<'a> {
+#[derive(Debug, Clone)]
+struct Parameters<F> {
+    a: F,
+    b: F,
+    group: Option<Group<F>>,
+}
+
+// A cyclic subgroup used for cryptography: its generator G, its prime order n, and the cofactor
+// h = #E / n. The generator is stored by its coordinates since a point borrows its curve.
+#[derive(Debug, Clone)]
+struct Group<F> {
+    generator_x: F,
+    generator_y: F,
+    order: BigUint,
+    cofactor: BigUint,
+    // Built on the first call to mul_generator
+    table: OnceLock<GeneratorTable<F>>,
+    endomorphism: Option<Endomorphism<F>>,
+}
+
+// An endomorphism (x, y) -> (beta * x, y) that acts on the group as multiplication by lambda, with
+// a basis (a1, b1), (a2, b2) of short vectors in the lattice of (x, y) with x + y * lambda = 0 (mod n)
+#[derive(Debug, Clone)]
+struct Endomorphism<F> {
+    beta: F,
+    a1: BigInt,
+    b1: BigInt,
+    a2: BigInt,
+    b2: BigInt,
+}
+
+// Jacobian coordinates (X, Y, Z) without the curve reference, for storing points inside a curve
+type Coordinates<F> = (F, F, F);
+
+// Multiples of G for fixed-base multiplication with 4-bit windows: windows[i][j] = (j + 1) *
+// 16**i * G, normalized to Z = 1 (or Z = 0 for infinity). Writing k in base 16, kG is the sum of
+// one entry per window, so it takes no doublings. Each entry is one too many multiples of 16**i,
+// which keeps the common case away from additions with infinity; offset cancels the excess.
+#[derive(Clone)]
+struct GeneratorTable<F> {
+    windows: Vec<Vec<Coordinates<F>>>,
+    offset: Coordinates<F>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CurvePoint<F = FieldElement> {
     is_infinity: bool,
-    x: FieldElement,
-    y: FieldElement,
-    curve: &'a EllipticCurve,
+    x: F,
+    y: F,
+    curve: EllipticCurve<F>,
+}
+
+// A point in Jacobian coordinates (X : Y : Z), standing for the affine point (X / Z**2, Y / Z**3).
+// Z = 0 is the point at infinity. Adding and doubling in this form need no field inversions, so
+// scalar multiplication runs here and pays for a single inversion when converting back to affine.
+#[derive(Debug, Clone)]
+struct JacobianPoint<'a, F> {
+    x: F,
+    y: F,
+    z: F,
+    curve: &'a EllipticCurve<F>,
 }
 
 impl EllipticCurve {
@@ -22,124 +92,920 @@ impl EllipticCurve {
         U: ToBigUint,
         V: ToBigUint + Clone,
     {
-        EllipticCurve {
-            a: FieldElement::new(a, prime.clone()),
-            b: FieldElement::new(b, prime),
-        }
+        EllipticCurve::try_new(a, b, prime).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates the curve y**2 = x**3 + ax + b over F_prime. Fails if the field is invalid or the
+    /// curve is singular (4a**3 + 27b**2 = 0), since a singular curve doesn't form a group.
+    pub fn try_new<T, U, V>(a: T, b: U, prime: V) -> Result<EllipticCurve, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+        V: ToBigUint + Clone,
+    {
+        let a = FieldElement::try_new(a, prime.clone())?;
+        let b = FieldElement::try_new(b, prime)?;
+        EllipticCurve::try_from_coefficients(a, b)
     }
     pub fn new_large(a: &[u8], b: &[u8], prime: &[u8]) -> EllipticCurve {
-        let a = BigUint::parse_bytes(a, 16).unwrap();
-        let b = BigUint::parse_bytes(b, 16).unwrap();
-        let prime = BigUint::parse_bytes(prime, 16).unwrap();
-        EllipticCurve::new(a, b, prime)
+        EllipticCurve::try_from_hex(a, b, prime).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates a curve from big-endian hex strings for a, b and the prime
+    pub fn try_from_hex(a: &[u8], b: &[u8], prime: &[u8]) -> Result<EllipticCurve, Error> {
+        EllipticCurve::try_new(parse_hex(a)?, parse_hex(b)?, parse_hex(prime)?)
     }
-
     pub fn get_prime(&self) -> &BigUint {
-        self.a.get_prime()
+        self.parameters.a.get_prime()
     }
     pub fn get_a(&self) -> &BigUint {
-        self.a.get_value()
+        self.parameters.a.get_value()
     }
     pub fn get_b(&self) -> &BigUint {
-        self.b.get_value()
+        self.parameters.b.get_value()
+    }
+}
+
+impl<F: Field> EllipticCurve<F> {
+    /// Creates the curve y**2 = x**3 + ax + b from coefficients in any field. Panics if the curve
+    /// is singular.
+    pub fn from_coefficients(a: F, b: F) -> EllipticCurve<F> {
+        EllipticCurve::try_from_coefficients(a, b).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_from_coefficients(a: F, b: F) -> Result<EllipticCurve<F>, Error> {
+        let four = a.element_from_integer(&4.to_biguint().unwrap());
+        let twenty_seven = a.element_from_integer(&27.to_biguint().unwrap());
+        let discriminant = four * a.squared() * a.clone() + twenty_seven * b.squared();
+        if discriminant.is_zero() {
+            return Err(Error::SingularCurve);
+        }
+        Ok(EllipticCurve {
+            parameters: Arc::new(Parameters { a, b, group: None }),
+        })
+    }
+    /// Attaches the group parameters used for signing and verification: a generator G = (x, y),
+    /// the order n of the subgroup it generates, and the cofactor h. Panics if G is not on the
+    /// curve, n is not a prime with n * G = 0, or h * n is not the number of points on the curve.
+    pub fn with_group<T, U, V, W>(self, x: T, y: U, order: V, cofactor: W) -> EllipticCurve<F>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+        V: ToBigUint,
+        W: ToBigUint,
+    {
+        self.try_with_group(x, y, order, cofactor)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_with_group<T, U, V, W>(
+        mut self,
+        x: T,
+        y: U,
+        order: V,
+        cofactor: W,
+    ) -> Result<EllipticCurve<F>, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+        V: ToBigUint,
+        W: ToBigUint,
+    {
+        let order = order.to_biguint().ok_or(Error::NegativeValue)?;
+        let cofactor = cofactor.to_biguint().ok_or(Error::NegativeValue)?;
+        let generator = CurvePoint::try_new(x, y, &self)?;
+        // Scalars are inverted mod n, which needs n to be prime
+        if !is_probable_prime(&order) || !generator.multiply_vartime(&order).is_infinity() {
+            return Err(Error::InvalidGroupOrder);
+        }
+        // Scalar multiplication reduces scalars mod h * n, which is only safe for h * n = #E
+        self.check_point_count(&(&order * &cofactor), &order)?;
+        // Drop the generator's handle first, so the parameters are only copied if the caller
+        // still holds another clone of the curve
+        let CurvePoint { x, y, .. } = generator;
+        Arc::make_mut(&mut self.parameters).group = Some(Group {
+            generator_x: x,
+            generator_y: y,
+            order,
+            cofactor,
+            table: OnceLock::new(),
+            endomorphism: None,
+        });
+        Ok(self)
+    }
+    /// Attaches an endomorphism (x, y) -> (beta * x, y) that maps every point P to lambda * P.
+    /// Variable-time multiplication then splits each scalar k into k1 + k2 * lambda with k1 and k2
+    /// about half as long (GLV), which halves the doublings. Panics if the curve has no group, the
+    /// cofactor isn't 1, or beta and lambda don't correspond.
+    pub fn with_endomorphism<T, U>(self, beta: T, lambda: U) -> EllipticCurve<F>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        self.try_with_endomorphism(beta, lambda)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_with_endomorphism<T, U>(
+        mut self,
+        beta: T,
+        lambda: U,
+    ) -> Result<EllipticCurve<F>, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        let generator = self.get_generator().ok_or(Error::MissingGroup)?;
+        let order = self.get_order().unwrap().clone();
+        let one = 1.to_biguint().unwrap();
+        let beta = self
+            .parameters
+            .a
+            .element_from_integer(&beta.to_biguint().ok_or(Error::NegativeValue)?);
+        let lambda = lambda.to_biguint().ok_or(Error::NegativeValue)? % &order;
+        // Both must be nontrivial cube roots of unity, and lambda * P = phi(P) can only be checked
+        // on G, so the group generated by G must be the whole curve
+        if self.get_cofactor() != Some(&one)
+            || beta == self.parameters.a.one()
+            || beta.squared() * beta.clone() != self.parameters.a.one()
+            || lambda.modpow(&3.to_biguint().unwrap(), &order) != one
+        {
+            return Err(Error::InvalidEndomorphism);
+        }
+        let image = CurvePoint {
+            is_infinity: false,
+            x: generator.x.clone() * beta.clone(),
+            y: generator.y.clone(),
+            curve: self.clone(),
+        };
+        if generator.multiply_vartime(&lambda) != image {
+            return Err(Error::InvalidEndomorphism);
+        }
+        drop((generator, image));
+        let endomorphism = Endomorphism::new(beta, &lambda, &order);
+        let parameters = Arc::make_mut(&mut self.parameters);
+        parameters.group.as_mut().unwrap().endomorphism = Some(endomorphism);
+        Ok(self)
+    }
+    /// Returns the generator G, if group parameters have been attached with with_group
+    pub fn get_generator(&self) -> Option<CurvePoint<F>> {
+        self.parameters.group.as_ref().map(|group| CurvePoint {
+            is_infinity: false,
+            x: group.generator_x.clone(),
+            y: group.generator_y.clone(),
+            curve: self.clone(),
+        })
+    }
+    /// Computes kG using a table of multiples of G that is built on the first call and kept with
+    /// the curve. Returns None if the curve has no group parameters. Like the `*` operator, the
+    /// table lookups are made without branching on the bits of k. Panics if k is a scalar modulo a
+    /// different order.
+    pub fn mul_generator(&self, scalar: &Scalar) -> Option<CurvePoint<F>> {
+        let group = self.parameters.group.as_ref()?;
+        self.check_scalar(scalar);
+        let table = group.table.get_or_init(|| GeneratorTable::new(self));
+        Some(table.multiply(scalar.get_value(), self))
+    }
+    /// Computes the sum of k_i * P_i over all terms with Straus' interleaving: every point gets a
+    /// small table of multiples, and a single chain of doublings is shared between all scalars,
+    /// instead of one chain per scalar. Variable time, so for public scalars only, such as in
+    /// signature verification. The scalars are taken mod n, so the points should lie in the group
+    /// generated by G. Panics if a point lies on a different curve, or if the curve has no group
+    /// parameters or a scalar is modulo a different order.
+    pub fn multi_scalar_mul(&self, terms: &[(&Scalar, &CurvePoint<F>)]) -> CurvePoint<F> {
+        let endomorphism = self
+            .parameters
+            .group
+            .as_ref()
+            .and_then(|group| group.endomorphism.as_ref());
+        let mut bases = Vec::with_capacity(2 * terms.len());
+        for (scalar, point) in terms {
+            if point.get_curve() != self {
+                panic!("Point {:?} is not on the curve", point);
+            }
+            self.check_scalar(scalar);
+            let base = JacobianPoint::from_affine(point);
+            let scalar = scalar.get_value().clone();
+            match endomorphism {
+                // kP = k1 * P + k2 * phi(P), where k1 and k2 are half as long but may be negative
+                Some(endomorphism) => {
+                    let (k1, k2) = endomorphism.decompose(&scalar, self.get_order().unwrap());
+                    let image = endomorphism.apply(&base);
+                    bases.push(signed_term(k1, base));
+                    bases.push(signed_term(k2, image));
+                }
+                None => bases.push((scalar, base)),
+            }
+        }
+        let mut tables = Vec::with_capacity(bases.len());
+        let mut scalars = Vec::with_capacity(bases.len());
+        for (scalar, base) in bases {
+            // tables[i][j] = j * P_i for the 4-bit digits j
+            let mut table = vec![JacobianPoint::infinity(self)];
+            for j in 1..16 {
+                table.push(table[j - 1].add(&base));
+            }
+            tables.push(table);
+            scalars.push(scalar);
+        }
+        let bits = scalars
+            .iter()
+            .map(|scalar| scalar.bits())
+            .max()
+            .unwrap_or(0);
+        let mut result = JacobianPoint::infinity(self);
+        for window in (0..bits.div_ceil(4)).rev() {
+            for _ in 0..4 {
+                result = result.double();
+            }
+            for (scalar, table) in scalars.iter().zip(&tables) {
+                let digit = (scalar >> (4 * window)) & 15.to_biguint().unwrap();
+                let digit = digit.to_bytes_le()[0] as usize;
+                if digit != 0 {
+                    result = result.add(&table[digit]);
+                }
+            }
+        }
+        result.to_affine()
+    }
+    // Checks that count is the number of points on the curve, given a prime n that divides it.
+    // Hasse's theorem puts #E within 2 * sqrt(p) of p + 1. When n > 4 * sqrt(p), that range holds
+    // only one multiple of n. Otherwise count * P = 0 is checked for a few points, which fails
+    // for most points unless count is a multiple of #E, and with it in the range, equal to it.
+    fn check_point_count(&self, count: &BigUint, order: &BigUint) -> Result<(), Error> {
+        let one = self.parameters.a.one();
+        let prime = (-one).to_integer() + 1u8;
+        let distance = count.to_bigint().unwrap() - (&prime + 1u8).to_bigint().unwrap();
+        let four_p = (&prime * 4u8).to_bigint().unwrap();
+        if &distance * &distance > four_p {
+            return Err(Error::InvalidCofactor);
+        }
+        if order * order > &prime * 16u8 {
+            return Ok(());
+        }
+        let mut checked = 0;
+        let mut x = 0.to_biguint().unwrap();
+        while checked < 8 && x < prime {
+            let x_element = self.parameters.a.element_from_integer(&x);
+            if let Some(y) = self.rhs(&x_element).sqrt() {
+                let point = CurvePoint {
+                    is_infinity: false,
+                    x: x_element,
+                    y,
+                    curve: self.clone(),
+                };
+                if !point.multiply_vartime(count).is_infinity() {
+                    return Err(Error::InvalidCofactor);
+                }
+                checked += 1;
+            }
+            x += 1u8;
+        }
+        Ok(())
+    }
+    /// Returns the order n of the subgroup generated by G
+    pub fn get_order(&self) -> Option<&BigUint> {
+        self.parameters.group.as_ref().map(|group| &group.order)
+    }
+    /// Returns the cofactor h, the number of points on the curve divided by n
+    pub fn get_cofactor(&self) -> Option<&BigUint> {
+        self.parameters.group.as_ref().map(|group| &group.cofactor)
+    }
+    fn check_scalar(&self, scalar: &Scalar) {
+        let order = self
+            .get_order()
+            .unwrap_or_else(|| panic!("{}", Error::MissingGroup));
+        if scalar.get_order() != order {
+            panic!("{}", Error::MismatchedOrders);
+        }
     }
-    pub fn is_valid(&self, x: &FieldElement, y: &FieldElement) -> bool {
-        y.pow(2) == x.pow(3) + &self.a * x + &self.b
+    pub fn is_valid(&self, x: &F, y: &F) -> bool {
+        y.squared() == self.rhs(x)
+    }
+    // x**3 + ax + b, the right hand side of the curve equation
+    fn rhs(&self, x: &F) -> F {
+        x.squared() * x.clone() + self.parameters.a.clone() * x.clone() + self.parameters.b.clone()
     }
 }
 
-impl<'a> CurvePoint<'a> {
-    pub fn new<T, U>(x: T, y: U, curve: &'a EllipticCurve) -> CurvePoint
+impl<F: Field> CurvePoint<F> {
+    pub fn new<T, U>(x: T, y: U, curve: &EllipticCurve<F>) -> CurvePoint<F>
     where
-        T: ToBigUint + std::fmt::Debug,
-        U: ToBigUint + std::fmt::Debug,
+        T: ToBigUint,
+        U: ToBigUint,
     {
+        CurvePoint::try_new(x, y, curve).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates the point (x, y), failing if it doesn't satisfy the curve equation
+    pub fn try_new<T, U>(x: T, y: U, curve: &EllipticCurve<F>) -> Result<CurvePoint<F>, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        let x = x.to_biguint().ok_or(Error::NegativeValue)?;
+        let y = y.to_biguint().ok_or(Error::NegativeValue)?;
         let new = CurvePoint {
             is_infinity: false,
-            x: FieldElement::new(x, curve.get_prime().clone()),
-            y: FieldElement::new(y, curve.get_prime().clone()),
-            curve,
+            x: curve.parameters.a.element_from_integer(&x),
+            y: curve.parameters.a.element_from_integer(&y),
+            curve: curve.clone(),
         };
-        if !new.is_infinity() && !curve.is_valid(&new.x, &new.y) {
-            panic!(
-                "Point ({:?}, {:?}) is not on curve y**2 = x**3 + {}*x + {}",
-                new.x.get_value(),
-                new.y.get_value(),
-                curve.get_a(),
-                curve.get_b()
-            );
+        if !curve.is_valid(&new.x, &new.y) {
+            return Err(Error::PointNotOnCurve);
         }
-        new
+        Ok(new)
     }
-    pub fn new_large(x: &[u8], y: &[u8], curve: &'a EllipticCurve) -> CurvePoint<'a> {
-        let x = BigUint::parse_bytes(x, 16).unwrap();
-        let y = BigUint::parse_bytes(y, 16).unwrap();
-        CurvePoint::new(x, y, curve)
+    pub fn new_large(x: &[u8], y: &[u8], curve: &EllipticCurve<F>) -> CurvePoint<F> {
+        CurvePoint::try_from_hex(x, y, curve).unwrap_or_else(|e| panic!("{}", e))
     }
-    pub fn from(other: &'a CurvePoint) -> CurvePoint<'a> {
-        if other.is_infinity() {
-            return CurvePoint::infinity(other.curve);
+    /// Creates a point from big-endian hex strings for x and y
+    pub fn try_from_hex(
+        x: &[u8],
+        y: &[u8],
+        curve: &EllipticCurve<F>,
+    ) -> Result<CurvePoint<F>, Error> {
+        CurvePoint::try_new(parse_hex(x)?, parse_hex(y)?, curve)
+    }
+    /// Adds two points, failing instead of panicking if they lie on different curves
+    pub fn try_add(&self, other: &CurvePoint<F>) -> Result<CurvePoint<F>, Error> {
+        if self.get_curve() != other.get_curve() {
+            return Err(Error::MismatchedCurves);
+        }
+        Ok(self + other)
+    }
+    /// Parses a point in SEC1 format, either uncompressed (0x04 || x || y) or compressed
+    /// (0x02 or 0x03 || x). Compressed points are decompressed by solving the curve equation for y.
+    pub fn from_sec(bytes: &[u8], curve: &EllipticCurve<F>) -> Result<CurvePoint<F>, Error> {
+        let len = curve.parameters.a.to_bytes().len();
+        let expected = match bytes.first() {
+            None => 1,
+            Some(0x00) => 1,
+            Some(0x02) | Some(0x03) => 1 + len,
+            Some(0x04) => 1 + 2 * len,
+            Some(&prefix) => return Err(Error::InvalidSecPrefix(prefix)),
+        };
+        if bytes.len() != expected {
+            return Err(Error::InvalidLength {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        if bytes[0] == 0x00 {
+            return Ok(CurvePoint::infinity(curve));
         }
-        CurvePoint::new(other.x.clone(), other.y.clone(), other.curve)
+
+        let x = curve
+            .parameters
+            .a
+            .element_from_bytes(&bytes[1..1 + len])
+            .ok_or(Error::PointNotOnCurve)?;
+        let y = if bytes[0] == 0x04 {
+            curve
+                .parameters
+                .a
+                .element_from_bytes(&bytes[1 + len..])
+                .ok_or(Error::PointNotOnCurve)?
+        } else {
+            // y**2 = x**3 + ax + b, so y is a square root of the right hand side
+            let beta = curve.rhs(&x).sqrt().ok_or(Error::PointNotOnCurve)?;
+            let y = if beta.is_odd() == (bytes[0] == 0x03) {
+                beta
+            } else {
+                -beta
+            };
+            // p is odd, so beta and p - beta have opposite parities, except when y = 0 is its own
+            // negation. Its only encoding is 0x02, so 0x03 with such an x is rejected.
+            if y.is_odd() != (bytes[0] == 0x03) {
+                return Err(Error::InvalidSecPrefix(bytes[0]));
+            }
+            y
+        };
+        if !curve.is_valid(&x, &y) {
+            return Err(Error::PointNotOnCurve);
+        }
+        Ok(CurvePoint {
+            is_infinity: false,
+            x,
+            y,
+            curve: curve.clone(),
+        })
+    }
+    /// Serializes the point in SEC1 format. Compressed points store only x and a prefix of 0x02
+    /// or 0x03 recording whether y is even or odd. The point at infinity is the single byte 0x00.
+    pub fn to_sec(&self, compressed: bool) -> Vec<u8> {
+        if self.is_infinity() {
+            return vec![0x00];
+        }
+        let x = self.x.to_bytes();
+        let mut sec = Vec::with_capacity(1 + 2 * x.len());
+        if compressed {
+            sec.push(if self.y.is_odd() { 0x03 } else { 0x02 });
+            sec.extend(x);
+        } else {
+            sec.push(0x04);
+            sec.extend(x);
+            sec.extend(self.y.to_bytes());
+        }
+        sec
+    }
+    pub fn from(other: &CurvePoint<F>) -> CurvePoint<F> {
+        other.clone()
     }
     pub fn is_infinity(&self) -> bool {
         self.is_infinity
     }
-    pub fn get_x(&self) -> &FieldElement {
+    pub fn get_x(&self) -> &F {
         &self.x
     }
-    pub fn get_y(&self) -> &FieldElement {
+    pub fn get_y(&self) -> &F {
         &self.y
     }
-    pub fn get_curve(&self) -> &'a EllipticCurve {
-        self.curve
+    pub fn get_curve(&self) -> &EllipticCurve<F> {
+        &self.curve
     }
-    pub fn infinity(curve: &'a EllipticCurve) -> CurvePoint {
+    pub fn infinity(curve: &EllipticCurve<F>) -> CurvePoint<F> {
         CurvePoint {
             is_infinity: true,
-            x: FieldElement::new(0, curve.get_prime().clone()),
-            y: FieldElement::new(0, curve.get_prime().clone()),
-            curve,
+            x: curve.parameters.a.zero(),
+            y: curve.parameters.a.zero(),
+            curve: curve.clone(),
         }
     }
+    /// Verifies the ECDSA signature over z against this point as the public key, using the
+    /// generator and order of its curve. Signatures whose r or s lies outside [1, N) are rejected
+    /// rather than reduced, as are all signatures on curves without group parameters and all
+    /// signatures checked against the point at infinity.
     #[allow(non_snake_case)]
-    pub fn verify_signature(&self, z: &[u8], r: &[u8], s: &[u8], G: &CurvePoint, N: &BigUint) -> bool {
-        let z = BigUint::parse_bytes(z, 16).unwrap();
-        let r = BigUint::parse_bytes(r, 16).unwrap();
-        let s = BigUint::parse_bytes(s, 16).unwrap();
+    pub fn verify_signature(&self, z: &MessageHash, sig: &Signature) -> bool {
+        // With Q at infinity, u * G + v * Q no longer depends on the key, and anyone can pick
+        // r = x(kG) and s = z / k to make it match
+        if self.is_infinity() {
+            return false;
+        }
+        let (G, N) = match (self.curve.get_generator(), self.curve.get_order()) {
+            (Some(G), Some(N)) => (G, N),
+            _ => return false,
+        };
+        if sig.check_range(N).is_err() {
+            return false;
+        }
+        let z = Scalar::from_bytes_reduced(z.as_bytes(), N);
+        let r = Scalar::new(sig.get_r().clone(), N);
+        let s_inv = Scalar::new(sig.get_s().clone(), N).invert().unwrap();
+        let u = &z * &s_inv;
+        let v = &r * &s_inv;
 
-        let s_inv = s.modpow(&(N - 2.to_biguint().unwrap()), N);
-        let u = &z * &s_inv % N;
-        let v = &r * &s_inv % N;
+        // u, v and the public key are all public, so the faster variable-time path is safe here
+        let R = self.curve.multi_scalar_mul(&[(&u, &G), (&v, self)]);
+        Scalar::new(R.get_x().to_integer(), N) == r
+    }
+    /// Like verify_signature, but also enforces Bitcoin Core's standardness policy by rejecting
+    /// signatures whose s is greater than N / 2
+    pub fn verify_signature_strict(&self, z: &MessageHash, sig: &Signature) -> bool {
+        match self.curve.get_order() {
+            Some(order) => sig.is_low_s(order) && self.verify_signature(z, sig),
+            None => false,
+        }
+    }
+    /// Multiplies the point by a public scalar, such as in signature verification. The running
+    /// time depends on the bits of the scalar, so it must never be used with a secret; the `*`
+    /// operator is the constant-time alternative. Panics under the same conditions as
+    /// multi_scalar_mul.
+    pub fn mul_vartime(&self, scalar: &Scalar) -> CurvePoint<F> {
+        self.curve.multi_scalar_mul(&[(scalar, self)])
+    }
+    // Double-and-add scalar multiplication by the full scalar. The loop works in Jacobian
+    // coordinates, so the only inversion is the conversion of the result back to affine.
+    fn multiply_vartime(&self, scalar: &BigUint) -> CurvePoint<F> {
+        let zero = 0.to_biguint().unwrap();
+        let one = 1.to_biguint().unwrap();
+        let mut rhs = scalar.clone();
+        let mut current = JacobianPoint::from_affine(self);
+        let mut result = JacobianPoint::infinity(&self.curve);
+        while rhs != zero {
+            if &rhs & &one != zero {
+                result = result.add(&current);
+            }
+            current = current.double();
+            rhs >>= 1;
+        }
+        result.to_affine()
+    }
+    // Montgomery ladder. Each bit costs exactly one addition and one doubling, and only decides
+    // which of R0 and R1 receives which result, through masked swaps rather than branches. R1 - R0
+    // = P throughout, so R0 ends as kP.
+    //
+    // The operation sequence depends only on the bit length of the curve, not on the scalar. This
+    // is not a full guarantee: see FieldElement about the arithmetic, and the addition formulas
+    // still branch in the rare case that R0 = -R1 or either is infinity.
+    fn multiply_ladder(&self, scalar: &BigUint) -> CurvePoint<F> {
+        let one = 1.to_biguint().unwrap();
+        let (scalar, bits, mut r0, mut r1) =
+            match (self.curve.get_order(), self.curve.get_cofactor()) {
+                (Some(order), Some(cofactor)) => {
+                    // Adding multiples of m = h * n doesn't change kP. One of k + m and k + 2m has
+                    // exactly len(m) + 1 bits, so the ladder can start from P and 2P at the top bit
+                    // instead of from infinity, whose additions would reveal the leading zeros of k
+                    let m = order * cofactor;
+                    let len = m.bits();
+                    let k1 = scalar % &m + &m;
+                    let k2 = &k1 + &m;
+                    let has_top_bit = ((&k1 >> len) & &one) == one;
+                    let scalar = select(&k1, &k2, has_top_bit, len + 1);
+                    let p = JacobianPoint::from_affine(self);
+                    (scalar, len, p.clone(), p.double())
+                }
+                // Without the group order, pad to the length of the field and start from infinity
+                _ => {
+                    let bits = cmp::max(scalar.bits(), 8 * self.x.to_bytes().len());
+                    (
+                        scalar.clone(),
+                        bits,
+                        JacobianPoint::infinity(&self.curve),
+                        JacobianPoint::from_affine(self),
+                    )
+                }
+            };
+        for i in (0..bits).rev() {
+            let bit = ((&scalar >> i) & &one) == one;
+            r0.conditional_swap(&mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.double();
+            r0.conditional_swap(&mut r1, bit);
+        }
+        r0.to_affine()
+    }
+}
 
-        return (G * u + self * v).get_x().get_value() == &r
+// Miller-Rabin with the first twelve primes as bases, which is exact below 3.3 * 10**24 and wrong
+// for larger n with negligible probability
+fn is_probable_prime(n: &BigUint) -> bool {
+    const BASES: [u8; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    let zero = 0.to_biguint().unwrap();
+    let one = 1.to_biguint().unwrap();
+    if n < &2.to_biguint().unwrap() {
+        return false;
+    }
+    for &base in BASES.iter() {
+        if n == &BigUint::from(base) {
+            return true;
+        }
+        if n % base == zero {
+            return false;
+        }
+    }
+    // Write n - 1 = d * 2**s with d odd
+    let n_less_one = n - &one;
+    let mut d = n_less_one.clone();
+    let mut s = 0;
+    while d.is_even() {
+        d >>= 1;
+        s += 1;
+    }
+    'bases: for &base in BASES.iter() {
+        let mut x = BigUint::from(base).modpow(&d, n);
+        if x == one || x == n_less_one {
+            continue;
+        }
+        for _ in 1..s {
+            x = &x * &x % n;
+            if x == n_less_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+// Returns a if choice is true and b otherwise, for values of at most bits bits, by masking
+// rather than branching
+fn select(a: &BigUint, b: &BigUint, choice: bool, bits: usize) -> BigUint {
+    let ones = (1.to_biguint().unwrap() << bits) - 1.to_biguint().unwrap();
+    let mask = ones * BigUint::from(choice as u8);
+    b ^ ((a ^ b) & mask)
+}
+
+impl<'a, F: Field> JacobianPoint<'a, F> {
+    fn from_affine(point: &'a CurvePoint<F>) -> JacobianPoint<'a, F> {
+        if point.is_infinity() {
+            return JacobianPoint::infinity(&point.curve);
+        }
+        JacobianPoint {
+            x: point.x.clone(),
+            y: point.y.clone(),
+            z: point.x.one(),
+            curve: &point.curve,
+        }
+    }
+    fn from_coordinates(
+        coordinates: &Coordinates<F>,
+        curve: &'a EllipticCurve<F>,
+    ) -> JacobianPoint<'a, F> {
+        JacobianPoint {
+            x: coordinates.0.clone(),
+            y: coordinates.1.clone(),
+            z: coordinates.2.clone(),
+            curve,
+        }
+    }
+    // Normalizes each point to Z = 1, or Z = 0 for infinity, and drops the curve reference. All the
+    // Z-coordinates are inverted together, so this costs one inversion however many points there are.
+    fn batch_to_coordinates(points: &[JacobianPoint<'a, F>]) -> Vec<Coordinates<F>> {
+        let mut z_inverses: Vec<F> = points.iter().map(|point| point.z.clone()).collect();
+        F::batch_invert(&mut z_inverses);
+        points
+            .iter()
+            .zip(z_inverses)
+            .map(|(point, z_inv)| {
+                let affine = point.normalize(z_inv);
+                let normalized = JacobianPoint::from_affine(&affine);
+                (normalized.x, normalized.y, normalized.z)
+            })
+            .collect()
+    }
+    fn infinity(curve: &'a EllipticCurve<F>) -> JacobianPoint<'a, F> {
+        JacobianPoint {
+            x: curve.parameters.a.one(),
+            y: curve.parameters.a.one(),
+            z: curve.parameters.a.zero(),
+            curve,
+        }
+    }
+    fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+    fn negate(&self) -> JacobianPoint<'a, F> {
+        JacobianPoint {
+            x: self.x.clone(),
+            y: -self.y.clone(),
+            z: self.z.clone(),
+            curve: self.curve,
+        }
+    }
+    fn conditional_swap(&mut self, other: &mut JacobianPoint<'a, F>, choice: bool) {
+        self.x.conditional_swap(&mut other.x, choice);
+        self.y.conditional_swap(&mut other.y, choice);
+        self.z.conditional_swap(&mut other.z, choice);
+    }
+    fn to_affine(&self) -> CurvePoint<F> {
+        match self.z.invert() {
+            Some(z_inv) => self.normalize(z_inv),
+            None => CurvePoint::infinity(self.curve),
+        }
+    }
+    // Converts to affine given 1 / Z, or returns infinity when Z = 0
+    fn normalize(&self, z_inv: F) -> CurvePoint<F> {
+        if self.is_infinity() {
+            return CurvePoint::infinity(self.curve);
+        }
+        let z_inv2 = z_inv.squared();
+        let z_inv3 = z_inv2.clone() * z_inv;
+        // The formulas preserve the curve equation, so the result needs no validity check
+        CurvePoint {
+            is_infinity: false,
+            x: self.x.clone() * z_inv2,
+            y: self.y.clone() * z_inv3,
+            curve: self.curve.clone(),
+        }
+    }
+    // Doubling formulas dbl-2007-bl from the Explicit-Formulas Database, for any a
+    fn double(&self) -> JacobianPoint<'a, F> {
+        // A point with y = 0 has a vertical tangent, so doubling it gives infinity
+        if self.is_infinity() || self.y.is_zero() {
+            return JacobianPoint::infinity(self.curve);
+        }
+        let xx = self.x.squared();
+        let yy = self.y.squared();
+        let yyyy = yy.squared();
+        let zz = self.z.squared();
+        // s = 4 * X * Y**2 and m = 3 * X**2 + a * Z**4 are the affine x and slope numerator,
+        // scaled by powers of Z
+        let s = twice(&twice(&(self.x.clone() * yy)));
+        let m = twice(&xx) + xx + self.curve.parameters.a.clone() * zz.squared();
+        let x3 = m.squared() - twice(&s);
+        let y3 = m * (s - x3.clone()) - twice(&twice(&twice(&yyyy)));
+        let z3 = twice(&(self.y.clone() * self.z.clone()));
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            curve: self.curve,
+        }
+    }
+    // Addition formulas add-2007-bl from the Explicit-Formulas Database
+    fn add(&self, other: &JacobianPoint<'a, F>) -> JacobianPoint<'a, F> {
+        if self.is_infinity() {
+            return other.clone();
+        } else if other.is_infinity() {
+            return self.clone();
+        }
+        let z1z1 = self.z.squared();
+        let z2z2 = other.z.squared();
+        // Bring both points to the common denominator Z1**2 * Z2**2 (and Z1**3 * Z2**3 for y)
+        let u1 = self.x.clone() * z2z2.clone();
+        let u2 = other.x.clone() * z1z1.clone();
+        let s1 = self.y.clone() * other.z.clone() * z2z2;
+        let s2 = other.y.clone() * self.z.clone() * z1z1;
+        let h = u2 - u1.clone();
+        let r = s2 - s1.clone();
+        if h.is_zero() {
+            // Same x-coordinate: either the same point or inverses of each other
+            if r.is_zero() {
+                return self.double();
+            }
+            return JacobianPoint::infinity(self.curve);
+        }
+        let hh = h.squared();
+        let hhh = h.clone() * hh.clone();
+        let u1hh = u1 * hh;
+        let x3 = r.squared() - hhh.clone() - twice(&u1hh);
+        let y3 = r * (u1hh - x3.clone()) - s1 * hhh;
+        let z3 = self.z.clone() * other.z.clone() * h;
+        JacobianPoint {
+            x: x3,
+            y: y3,
+            z: z3,
+            curve: self.curve,
+        }
     }
 }
 
-impl PartialEq for EllipticCurve {
+fn twice<F: Field>(value: &F) -> F {
+    value.clone() + value.clone()
+}
+
+// Turns a signed scalar and its point into the unsigned scalar for the negated point if needed
+fn signed_term<F: Field>(
+    scalar: BigInt,
+    point: JacobianPoint<'_, F>,
+) -> (BigUint, JacobianPoint<'_, F>) {
+    if scalar.sign() == Sign::Minus {
+        ((-scalar).to_biguint().unwrap(), point.negate())
+    } else {
+        (scalar.to_biguint().unwrap(), point)
+    }
+}
+
+impl<F: Field> Endomorphism<F> {
+    // Finds the short lattice basis from the extended Euclidean algorithm on n and lambda, as in
+    // Guide to Elliptic Curve Cryptography, algorithm 3.74
+    fn new(beta: F, lambda: &BigUint, order: &BigUint) -> Endomorphism<F> {
+        let n = order.to_bigint().unwrap();
+        // Each remainder r satisfies r = t * lambda (mod n), so (r, -t) lies in the lattice.
+        // Stop at the first remainder below sqrt(n)
+        let (mut r0, mut r1) = (n.clone(), lambda.to_bigint().unwrap());
+        let (mut t0, mut t1) = (0.to_bigint().unwrap(), 1.to_bigint().unwrap());
+        while &r1 * &r1 >= n {
+            let q = &r0 / &r1;
+            let r2 = &r0 - &q * &r1;
+            let t2 = &t0 - &q * &t1;
+            r0 = std::mem::replace(&mut r1, r2);
+            t0 = std::mem::replace(&mut t1, t2);
+        }
+        let q = &r0 / &r1;
+        let r2 = &r0 - &q * &r1;
+        let t2 = &t0 - &q * &t1;
+        // The second vector is the shorter of its neighbours in the remainder sequence
+        let (a2, b2) = if &r0 * &r0 + &t0 * &t0 <= &r2 * &r2 + &t2 * &t2 {
+            (r0, -t0)
+        } else {
+            (r2, -t2)
+        };
+        Endomorphism {
+            beta,
+            a1: r1,
+            b1: -t1,
+            a2,
+            b2,
+        }
+    }
+    // Splits k into (k1, k2) with k = k1 + k2 * lambda (mod n), by subtracting the lattice vector
+    // closest to (k, 0)
+    fn decompose(&self, scalar: &BigUint, order: &BigUint) -> (BigInt, BigInt) {
+        let n = order.to_bigint().unwrap();
+        let k = scalar.to_bigint().unwrap();
+        // Rounded division x / n
+        let two_n: BigInt = &n * 2;
+        let round = |x: BigInt| -> BigInt {
+            let doubled: BigInt = x * 2 + &n;
+            doubled.div_floor(&two_n)
+        };
+        let c1 = round(&self.b2 * &k);
+        let c2 = round(-&self.b1 * &k);
+        let k1 = k - &c1 * &self.a1 - &c2 * &self.a2;
+        let k2 = -c1 * &self.b1 - c2 * &self.b2;
+        (k1, k2)
+    }
+    fn apply<'a>(&self, point: &JacobianPoint<'a, F>) -> JacobianPoint<'a, F> {
+        // x = X / Z**2, so scaling X scales x
+        JacobianPoint {
+            x: point.x.clone() * self.beta.clone(),
+            y: point.y.clone(),
+            z: point.z.clone(),
+            curve: point.curve,
+        }
+    }
+}
+
+impl<F: Field> GeneratorTable<F> {
+    fn new(curve: &EllipticCurve<F>) -> GeneratorTable<F> {
+        let generator = curve.get_generator().unwrap();
+        let order = curve.get_order().unwrap();
+        let count = order.bits().div_ceil(4);
+        let mut entries = Vec::with_capacity(16 * count + 1);
+        // base = 16**i * G for the current window
+        let mut base = JacobianPoint::from_affine(&generator);
+        for _ in 0..count {
+            let mut multiple = base.clone();
+            for _ in 0..16 {
+                entries.push(multiple.clone());
+                multiple = multiple.add(&base);
+            }
+            base = base.double().double().double().double();
+        }
+        // The entries add sum(16**i) * G beyond kG, so offset = -sum(16**i) * G
+        let mut excess = 0.to_biguint().unwrap();
+        for _ in 0..count {
+            excess = (excess << 4) + 1.to_biguint().unwrap();
+        }
+        let offset = generator.multiply_vartime(&((order - excess % order) % order));
+        entries.push(JacobianPoint::from_affine(&offset));
+
+        let mut entries = JacobianPoint::batch_to_coordinates(&entries);
+        let offset = entries.pop().unwrap();
+        GeneratorTable {
+            windows: entries.chunks(16).map(|window| window.to_vec()).collect(),
+            offset,
+        }
+    }
+    // Computes kG for 0 <= k < n. Every window is read in full, and the wanted entry is picked out
+    // with masked swaps, so the memory access pattern doesn't depend on k.
+    fn multiply(&self, scalar: &BigUint, curve: &EllipticCurve<F>) -> CurvePoint<F> {
+        let mut bytes = scalar.to_bytes_le();
+        bytes.resize(self.windows.len().div_ceil(2), 0);
+        let mut result = JacobianPoint::from_coordinates(&self.offset, curve);
+        for (i, window) in self.windows.iter().enumerate() {
+            let digit = ((bytes[i / 2] >> (4 * (i % 2))) & 0x0f) as usize;
+            let mut entry = JacobianPoint::from_coordinates(&window[0], curve);
+            for (j, coordinates) in window.iter().enumerate().skip(1) {
+                let mut candidate = JacobianPoint::from_coordinates(coordinates, curve);
+                entry.conditional_swap(&mut candidate, j == digit);
+            }
+            result = result.add(&entry);
+        }
+        result.to_affine()
+    }
+}
+
+// The table holds hundreds of points, so leave them out of debug output
+impl<F> fmt::Debug for GeneratorTable<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GeneratorTable {{ windows: {} }}", self.windows.len())
+    }
+}
+
+// Curves with the same equation but different groups are different curves, since scalars are
+// only meaningful modulo their own group order
+impl<F: PartialEq> PartialEq for EllipticCurve<F> {
     fn eq(&self, other: &Self) -> bool {
-        self.get_a() == other.get_a() && self.get_b() == other.get_b()
+        Arc::ptr_eq(&self.parameters, &other.parameters)
+            || (self.parameters.a == other.parameters.a
+                && self.parameters.b == other.parameters.b
+                && self.parameters.group == other.parameters.group)
     }
 }
 
-impl<'a> PartialEq for CurvePoint<'a> {
+// The table and endomorphism only speed up multiplication, so they take no part in equality
+impl<F: PartialEq> PartialEq for Group<F> {
     fn eq(&self, other: &Self) -> bool {
-        if self.get_curve() != other.get_curve() {
+        self.generator_x == other.generator_x
+            && self.generator_y == other.generator_y
+            && self.order == other.order
+            && self.cofactor == other.cofactor
+    }
+}
+
+impl<F: PartialEq> PartialEq for CurvePoint<F> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.curve != other.curve {
             return false;
         }
-        if self.is_infinity() {
-            return other.is_infinity();
+        if self.is_infinity {
+            return other.is_infinity;
         }
-        if other.is_infinity() {
+        if other.is_infinity {
             return false;
         }
-        self.get_x() == other.get_x() && self.get_y() == other.get_y()
+        self.x == other.x && self.y == other.y
     }
 }
 
-impl<'a> std::ops::Add for CurvePoint<'a> {
-    type Output = CurvePoint<'a>;
-    fn add(self, other: Self) -> CurvePoint<'a> {
+impl<F: Field> std::ops::Add for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn add(self, other: Self) -> CurvePoint<F> {
+        &self + &other
+    }
+}
+
+impl<F: Field> std::ops::Add for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn add(self, other: Self) -> CurvePoint<F> {
         if self.get_curve() != other.get_curve() {
             panic!(
                 "Addition Error: Points {:?} and {:?} are not on the same curve",
@@ -147,10 +1013,11 @@ impl<'a> std::ops::Add for CurvePoint<'a> {
             );
         }
         if other.is_infinity() {
-            return self;
+            return self.clone();
         } else if self.is_infinity() {
-            return other;
+            return other.clone();
         }
+
         let x1 = self.get_x();
         let x2 = other.get_x();
         let y1 = self.get_y();
@@ -164,111 +1031,100 @@ impl<'a> std::ops::Add for CurvePoint<'a> {
                 }
                 // Case 2: they're the same point. Take the derivative to find tangent line at that point
                 // y**2 = x**3 + ax + b => 2y * dy = 3(x**2) + a => dy / dx = 3(x**2) + a / 2y
-                // Thus slope = (3(x**2) + a) / 2y. Note that if 7=0, slope is infinite, so we should return the point at infinity
-                if y1.get_value() == &0.to_biguint().unwrap() {
+                // Thus slope = (3(x**2) + a) / 2y. Note that if y=0, slope is infinite, so we should return the point at infinity
+                if y1.is_zero() {
                     return CurvePoint::infinity(self.get_curve());
                 }
-                let mut numerator = x1.pow(2) * 3; // 3 x**2
-                numerator.add_int_ref(self.get_curve().get_a()); // 3x**2 + a
-                numerator / (y1 * 2)
+                let xx = x1.squared();
+                let numerator = twice(&xx) + xx + self.curve.parameters.a.clone(); // 3x**2 + a
+                numerator * twice(y1).invert().unwrap()
             } else {
                 // Otherwise slope is rise over run
-                (y2 - y1) / (x2 - x1)
+                (y2.clone() - y1.clone()) * (x2.clone() - x1.clone()).invert().unwrap()
             }
         };
         // x3 = slope**2 - x1 - x2. Proof too large for comments here. See Readme
-        let x3 = slope.pow(2) - x1 - x2;
+        let x3 = slope.squared() - x1.clone() - x2.clone();
         // y3 = slope * (x1 - x3) - y1
-        let y3 = (slope * (x1 - &x3)) - y1;
-        CurvePoint::new(x3, y3, self.get_curve())
+        let y3 = slope * (x1.clone() - x3.clone()) - y1.clone();
+        CurvePoint {
+            is_infinity: false,
+            x: x3,
+            y: y3,
+            curve: self.curve.clone(),
+        }
     }
 }
 
-impl<'a> std::ops::Add for &CurvePoint<'a> {
-    type Output = CurvePoint<'a>;
-    fn add(self, other: Self) -> CurvePoint<'a> {
-        if self.get_curve() != other.get_curve() {
-            panic!(
-                "Addition Error: Points {:?} and {:?} are not on the same curve",
-                self, other
-            );
-        }
-        if other.is_infinity() {
-            return CurvePoint::new(self.get_x(), self.get_y(), self.get_curve());
-        } else if self.is_infinity() {
-            return CurvePoint::new(other.get_x(), other.get_y(), self.get_curve());
-        }
+// The `*` operator multiplies with the Montgomery ladder, which is safe for secrets. Scalars must
+// share the curve's group order, while plain integers also work for points outside the group.
+impl<F: Field> std::ops::Mul<&Scalar> for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: &Scalar) -> CurvePoint<F> {
+        self.curve.check_scalar(rhs);
+        self.multiply_ladder(rhs.get_value())
+    }
+}
 
-        let x1 = self.get_x();
-        let x2 = other.get_x();
-        let y1 = self.get_y();
-        let y2 = other.get_y();
-        let slope = {
-            // If the points share an x-coordinate, we have two special cases
-            if x1 == x2 {
-                // Case 1: If they lie on a vertical line they add to infinity
-                if y1 != y2 {
-                    return CurvePoint::infinity(self.get_curve());
-                }
-                // Case 2: they're the same point. Take the derivative to find tangent line at that point
-                // y**2 = x**3 + ax + b => 2y * dy = 3(x**2) + a => dy / dx = 3(x**2) + a / 2y
-                // Thus slope = (3(x**2) + a) / 2y
-                let mut numerator = x1.pow(2) * 3; // 3 x**2
-                numerator.add_int_ref(self.get_curve().get_a()); // 3x**2 + a
-                numerator / (y1 * 2)
-            } else {
-                // Otherwise slope is rise over run
-                (y2 - y1) / (x2 - x1)
-            }
-        };
-        // x3 = slope**2 - x1 - x2. Proof too large for comments here. See Readme
-        let x3 = slope.pow(2) - x1 - x2;
-        // y3 = slope * (x1 - x3) - y1
-        let y3 = (slope * (x1 - &x3)) - y1;
-        CurvePoint::new(x3, y3, self.get_curve())
+impl<F: Field> std::ops::Mul<Scalar> for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: Scalar) -> CurvePoint<F> {
+        self * &rhs
     }
 }
 
-impl<'a, Rhs> std::ops::Mul<Rhs> for CurvePoint<'a>
-where
-    Rhs: ToBigUint,
-{
-    type Output = CurvePoint<'a>;
-    fn mul(self, rhs: Rhs) -> CurvePoint<'a> {
-        let zero = 0.to_biguint().unwrap();
-        let one = 1.to_biguint().unwrap();
-        let mut rhs = rhs.to_biguint().unwrap();
-        let mut current = CurvePoint::new(self.get_x(), self.get_y(), self.get_curve());
-        let mut result = CurvePoint::infinity(&self.curve);
-        while rhs != zero {
-            if &rhs & &one != zero {
-                result = &result + &current;
-            }
-            current = &current + &current;
-            rhs >>= 1;
-        }
-        result
+impl<F: Field> std::ops::Mul<&Scalar> for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: &Scalar) -> CurvePoint<F> {
+        &self * rhs
     }
 }
 
-impl<'a, Rhs> std::ops::Mul<Rhs> for &CurvePoint<'a>
-where
-    Rhs: ToBigUint,
-{
-    type Output = CurvePoint<'a>;
-    fn mul(self, rhs: Rhs) -> CurvePoint<'a> {
-        let zero = 0.to_biguint().unwrap();
-        let one = 1.to_biguint().unwrap();
-        let mut rhs = rhs.to_biguint().unwrap();
-        let mut current = CurvePoint::new(self.get_x(), self.get_y(), self.get_curve());
-        let mut result = CurvePoint::infinity(&self.curve);
-        while rhs != zero {
-            if &rhs & &one != zero {
-                result = &result + &current;
-            }
-            current = &current + &current;
-            rhs >>= 1;
-        }
-        result
+impl<F: Field> std::ops::Mul<Scalar> for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: Scalar) -> CurvePoint<F> {
+        &self * &rhs
+    }
+}
+
+impl<F: Field> std::ops::Mul<&BigUint> for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: &BigUint) -> CurvePoint<F> {
+        self.multiply_ladder(rhs)
+    }
+}
+
+impl<F: Field> std::ops::Mul<BigUint> for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: BigUint) -> CurvePoint<F> {
+        self.multiply_ladder(&rhs)
+    }
+}
+
+impl<F: Field> std::ops::Mul<&BigUint> for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: &BigUint) -> CurvePoint<F> {
+        self.multiply_ladder(rhs)
+    }
+}
+
+impl<F: Field> std::ops::Mul<BigUint> for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: BigUint) -> CurvePoint<F> {
+        self.multiply_ladder(&rhs)
+    }
+}
+
+impl<F: Field> std::ops::Mul<u32> for &CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: u32) -> CurvePoint<F> {
+        self.multiply_ladder(&BigUint::from(rhs))
+    }
+}
+
+impl<F: Field> std::ops::Mul<u32> for CurvePoint<F> {
+    type Output = CurvePoint<F>;
+    fn mul(self, rhs: u32) -> CurvePoint<F> {
+        self.multiply_ladder(&BigUint::from(rhs))
     }
 }
@@ -0,0 +1,238 @@
+use crate::error::Error;
+use crate::hash::hash256;
+use num_bigint::BigUint;
+
+/// Parses a big-endian hex string into an integer. Unlike BigUint::parse_bytes, this rejects
+/// empty input, signs and digit separators rather than silently accepting them.
+pub fn parse_hex(hex: &[u8]) -> Result<BigUint, Error> {
+    if hex.is_empty() || !hex.iter().all(u8::is_ascii_hexdigit) {
+        return Err(Error::InvalidHex);
+    }
+    BigUint::parse_bytes(hex, 16).ok_or(Error::InvalidHex)
+}
+
+/// Left-pads the big-endian encoding of value with zeros to exactly len bytes. Fails if the value
+/// does not fit.
+pub fn to_fixed_bytes(value: &BigUint, len: usize) -> Result<Vec<u8>, Error> {
+    let bytes = value.to_bytes_be();
+    if bytes.len() > len {
+        return Err(Error::InvalidLength {
+            expected: len,
+            found: bytes.len(),
+        });
+    }
+    let mut padded = vec![0u8; len - bytes.len()];
+    padded.extend_from_slice(&bytes);
+    Ok(padded)
+}
+
+/// Encodes n as a Bitcoin CompactSize integer, the variable-length prefix used for lengths
+pub fn encode_varint(n: u64) -> Vec<u8> {
+    match n {
+        0..=0xfc => vec![n as u8],
+        0xfd..=0xffff => [&[0xfd], &(n as u16).to_le_bytes()[..]].concat(),
+        0x10000..=0xffff_ffff => [&[0xfe], &(n as u32).to_le_bytes()[..]].concat(),
+        _ => [&[0xff], &n.to_le_bytes()[..]].concat(),
+    }
+}
+
+const BASE58_ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Encodes data in base58 with a 4-byte checksum, the first bytes of hash256(data), appended.
+/// Each leading zero byte becomes a leading '1'.
+pub fn base58check_encode(data: &[u8]) -> String {
+    let mut payload = data.to_vec();
+    payload.extend_from_slice(&hash256(data)[..4]);
+    let zeros = payload.iter().take_while(|&&byte| byte == 0).count();
+    let mut value = BigUint::from_bytes_be(&payload);
+    let base = BigUint::from(58u8);
+    let mut digits = Vec::new();
+    while value > BigUint::from(0u8) {
+        let remainder = (&value % &base).to_bytes_le()[0];
+        digits.push(BASE58_ALPHABET[remainder as usize]);
+        value /= &base;
+    }
+    digits.extend(std::iter::repeat_n(b'1', zeros));
+    digits.reverse();
+    String::from_utf8(digits).unwrap()
+}
+
+/// Decodes a base58check string and returns the data without its checksum
+pub fn base58check_decode(encoded: &str) -> Result<Vec<u8>, Error> {
+    let zeros = encoded.bytes().take_while(|&byte| byte == b'1').count();
+    let mut value = BigUint::from(0u8);
+    for byte in encoded.bytes() {
+        let digit = BASE58_ALPHABET
+            .iter()
+            .position(|&c| c == byte)
+            .ok_or(Error::InvalidBase58)?;
+        value = value * 58u8 + digit;
+    }
+    let mut payload = vec![0u8; zeros];
+    if value > BigUint::from(0u8) {
+        payload.extend(value.to_bytes_be());
+    }
+    if payload.len() < 4 {
+        return Err(Error::InvalidBase58);
+    }
+    let (data, checksum) = payload.split_at(payload.len() - 4);
+    if hash256(data)[..4] != *checksum {
+        return Err(Error::InvalidChecksum);
+    }
+    Ok(data.to_vec())
+}
+
+const BECH32_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+// The BCH checksum of BIP 173 over values of 5 bits
+fn bech32_polymod(values: &[u8]) -> u32 {
+    const GENERATORS: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    let mut checksum = 1u32;
+    for &value in values {
+        let top = checksum >> 25;
+        checksum = ((checksum & 0x1ffffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                checksum ^= generator;
+            }
+        }
+    }
+    checksum
+}
+
+// The human-readable part enters the checksum as its high bits, a zero, then its low bits
+fn bech32_hrp_expand(hrp: &str) -> Vec<u8> {
+    let mut expanded: Vec<u8> = hrp.bytes().map(|c| c >> 5).collect();
+    expanded.push(0);
+    expanded.extend(hrp.bytes().map(|c| c & 31));
+    expanded
+}
+
+/// Encodes 5-bit values with a human-readable part as bech32 (BIP 173)
+pub fn bech32_encode(hrp: &str, data: &[u8]) -> String {
+    let mut values = bech32_hrp_expand(hrp);
+    values.extend_from_slice(data);
+    values.extend_from_slice(&[0; 6]);
+    let polymod = bech32_polymod(&values) ^ 1;
+    let checksum = (0..6).map(|i| ((polymod >> (5 * (5 - i))) & 31) as u8);
+    let mut encoded = format!("{}1", hrp);
+    for value in data.iter().copied().chain(checksum) {
+        encoded.push(BECH32_CHARSET[value as usize] as char);
+    }
+    encoded
+}
+
+/// Decodes a bech32 string into its lowercase human-readable part and 5-bit data values, without
+/// the checksum. Mixed case strings are rejected.
+pub fn bech32_decode(encoded: &str) -> Result<(String, Vec<u8>), Error> {
+    let lowercase = encoded.to_lowercase();
+    if (encoded != lowercase && encoded != encoded.to_uppercase()) || encoded.len() > 90 {
+        return Err(Error::InvalidBech32);
+    }
+    // The separator is the last '1', since the human-readable part may contain ones
+    let separator = lowercase.rfind('1').ok_or(Error::InvalidBech32)?;
+    let (hrp, data) = (&lowercase[..separator], &lowercase[separator + 1..]);
+    if hrp.is_empty() || data.len() < 6 || hrp.bytes().any(|c| !(33..=126).contains(&c)) {
+        return Err(Error::InvalidBech32);
+    }
+    let mut values = Vec::with_capacity(data.len());
+    for byte in data.bytes() {
+        let value = BECH32_CHARSET
+            .iter()
+            .position(|&c| c == byte)
+            .ok_or(Error::InvalidBech32)?;
+        values.push(value as u8);
+    }
+    let mut checked = bech32_hrp_expand(hrp);
+    checked.extend_from_slice(&values);
+    if bech32_polymod(&checked) != 1 {
+        return Err(Error::InvalidChecksum);
+    }
+    values.truncate(values.len() - 6);
+    Ok((hrp.to_string(), values))
+}
+
+/// Regroups a sequence of from-bit values into to-bit values, as between bytes and bech32's 5-bit
+/// values. With pad, leftover bits are zero padded into a final value; without it, they must be
+/// fewer than from bits and all zero. Returns None if the input doesn't fit these rules.
+pub fn convert_bits(data: &[u8], from: u32, to: u32, pad: bool) -> Option<Vec<u8>> {
+    let mut accumulator = 0u32;
+    let mut bits = 0;
+    let max = (1u32 << to) - 1;
+    let mut converted = Vec::new();
+    for &value in data {
+        if (value as u32) >> from != 0 {
+            return None;
+        }
+        accumulator = (accumulator << from) | value as u32;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            converted.push(((accumulator >> bits) & max) as u8);
+        }
+    }
+    if pad {
+        if bits > 0 {
+            converted.push(((accumulator << (to - bits)) & max) as u8);
+        }
+    } else if bits >= from || (accumulator << (to - bits)) & max != 0 {
+        return None;
+    }
+    Some(converted)
+}
+
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes data in standard base64 with padding (RFC 4648)
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let bits = u32::from_be_bytes([0, group[0], group[1], group[2]]);
+        // n bytes fill n + 1 characters, and the rest of the group of four is padding
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[((bits >> (18 - 6 * i)) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Decodes standard padded base64, rejecting unpadded input and nonzero unused bits
+pub fn base64_decode(encoded: &str) -> Result<Vec<u8>, Error> {
+    let bytes = encoded.as_bytes();
+    if !bytes.len().is_multiple_of(4) {
+        return Err(Error::InvalidBase64);
+    }
+    let padding = bytes.iter().rev().take_while(|&&c| c == b'=').count();
+    if padding > 2 {
+        return Err(Error::InvalidBase64);
+    }
+    let mut decoded = Vec::with_capacity(bytes.len() / 4 * 3);
+    let groups = bytes.len() / 4;
+    for (i, group) in bytes.chunks(4).enumerate() {
+        let used = if i + 1 == groups { 4 - padding } else { 4 };
+        let mut bits = 0u32;
+        for (j, &c) in group.iter().enumerate() {
+            let value = match j < used {
+                true => BASE64_ALPHABET
+                    .iter()
+                    .position(|&a| a == c)
+                    .ok_or(Error::InvalidBase64)? as u32,
+                false => 0,
+            };
+            bits = (bits << 6) | value;
+        }
+        let group_bytes = &bits.to_be_bytes()[1..];
+        let length = used - 1;
+        if group_bytes[length..].iter().any(|&byte| byte != 0) {
+            return Err(Error::InvalidBase64);
+        }
+        decoded.extend_from_slice(&group_bytes[..length]);
+    }
+    Ok(decoded)
+}
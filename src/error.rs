@@ -0,0 +1,127 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A negative integer was supplied where an unsigned value was expected
+    NegativeValue,
+    /// A field modulus was less than 2
+    InvalidModulus,
+    /// An operation combined elements of fields with different primes
+    MismatchedPrimes,
+    /// An operation combined scalars modulo different group orders
+    MismatchedOrders,
+    /// An element was divided by zero
+    DivisionByZero,
+    /// The curve parameters give 4a**3 + 27b**2 = 0, so the curve has a cusp or self-intersection
+    SingularCurve,
+    /// An operation combined points on different curves
+    MismatchedCurves,
+    /// The claimed group order n is not a prime with n * G = 0
+    InvalidGroupOrder,
+    /// The claimed cofactor h does not make h * n the number of points on the curve
+    InvalidCofactor,
+    /// The claimed endomorphism does not act on the group as multiplication by lambda
+    InvalidEndomorphism,
+    /// The input contained characters that are not hexadecimal digits, or was empty
+    InvalidHex,
+    /// The input contained characters outside the base58 alphabet, or was too short
+    InvalidBase58,
+    /// The input was not valid padded base64
+    InvalidBase64,
+    /// The input was not a well-formed bech32 string
+    InvalidBech32,
+    /// A base58check or bech32 checksum did not match its data
+    InvalidChecksum,
+    /// The string is not a supported mainnet P2PKH or P2WPKH address
+    InvalidAddress,
+    /// A signed message's header byte was outside the range 27 to 42
+    InvalidSignatureHeader(u8),
+    /// The input did not have the expected number of bytes
+    InvalidLength { expected: usize, found: usize },
+    /// A scalar, such as a signature's r or s, was outside the range [1, N)
+    ScalarOutOfRange,
+    /// The point at infinity was supplied where a public key was expected
+    PointAtInfinity,
+    /// The operation needs a generator and group order, but the curve has none
+    MissingGroup,
+    /// A recovery id was greater than 3
+    InvalidRecoveryId(u8),
+    /// A SEC1 encoded point began with a byte other than 0x00, 0x02, 0x03 or 0x04, or with 0x03 for
+    /// a point whose y-coordinate is 0
+    InvalidSecPrefix(u8),
+    /// The coordinates do not satisfy the curve equation
+    PointNotOnCurve,
+    /// A DER encoded signature violated the strict encoding rules of BIP 66
+    InvalidDer(DerError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerError {
+    /// The signature is shorter than 8 or longer than 72 bytes
+    BadTotalLength,
+    /// The first byte is not the compound structure tag 0x30
+    MissingSequence,
+    /// The length bytes do not match the actual lengths of the structure or its integers
+    BadLength,
+    /// An element of the structure is not tagged as an integer (0x02)
+    MissingInteger,
+    /// An integer has a length of zero
+    EmptyInteger,
+    /// An integer has its sign bit set
+    NegativeInteger,
+    /// An integer begins with a zero byte that is not needed to clear the sign bit
+    ExcessPadding,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NegativeValue => write!(f, "Value must not be negative"),
+            Error::InvalidModulus => write!(f, "Modulus must be at least 2"),
+            Error::MismatchedPrimes => write!(f, "Primes don't match!"),
+            Error::MismatchedOrders => write!(f, "Scalars have different group orders"),
+            Error::DivisionByZero => write!(f, "Division by zero"),
+            Error::SingularCurve => write!(f, "Curve is singular"),
+            Error::MismatchedCurves => write!(f, "Points are not on the same curve"),
+            Error::InvalidGroupOrder => write!(f, "Generator does not have the given order"),
+            Error::InvalidCofactor => write!(f, "Cofactor does not match the number of points"),
+            Error::InvalidEndomorphism => write!(f, "Endomorphism does not match lambda"),
+            Error::InvalidHex => write!(f, "Invalid hex string"),
+            Error::InvalidBase58 => write!(f, "Invalid base58 string"),
+            Error::InvalidBase64 => write!(f, "Invalid base64 string"),
+            Error::InvalidBech32 => write!(f, "Invalid bech32 string"),
+            Error::InvalidChecksum => write!(f, "Checksum does not match"),
+            Error::InvalidAddress => write!(f, "Unsupported or malformed address"),
+            Error::InvalidSignatureHeader(header) => {
+                write!(f, "Invalid signed message header byte {}", header)
+            }
+            Error::InvalidLength { expected, found } => {
+                write!(f, "Expected {} bytes, found {}", expected, found)
+            }
+            Error::ScalarOutOfRange => write!(f, "Scalar is not in the range [1, N)"),
+            Error::PointAtInfinity => write!(f, "The point at infinity is not a valid public key"),
+            Error::MissingGroup => write!(f, "Curve has no group parameters"),
+            Error::InvalidRecoveryId(recid) => write!(f, "Invalid recovery id {}", recid),
+            Error::InvalidSecPrefix(prefix) => write!(f, "Invalid SEC1 prefix {:#04x}", prefix),
+            Error::PointNotOnCurve => write!(f, "Point is not on the curve"),
+            Error::InvalidDer(reason) => write!(f, "Invalid DER signature: {}", reason),
+        }
+    }
+}
+
+impl fmt::Display for DerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DerError::BadTotalLength => "length must be between 8 and 72 bytes",
+            DerError::MissingSequence => "missing compound structure tag",
+            DerError::BadLength => "length bytes don't match the encoded data",
+            DerError::MissingInteger => "missing integer tag",
+            DerError::EmptyInteger => "zero-length integer",
+            DerError::NegativeInteger => "negative integer",
+            DerError::ExcessPadding => "integer has excess leading zeros",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl std::error::Error for Error {}
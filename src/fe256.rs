@@ -0,0 +1,276 @@
+use crate::field::Field;
+use num_bigint::BigUint;
+use std::fmt;
+
+// The secp256k1 prime p = 2**256 - 2**32 - 977, as little-endian 64-bit limbs
+const P: [u64; 4] = [
+    0xffff_fffe_ffff_fc2f,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+// 2**256 - p. Since 2**256 = C (mod p), the high half of a product folds into the low half by
+// multiplying it by C, which is small enough that two folds finish the reduction.
+const C: u64 = 0x1_0000_03d1;
+// p - 2, for inversion by Fermat's little theorem
+const P_MINUS_2: [u64; 4] = [
+    0xffff_fffe_ffff_fc2d,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+];
+// (p + 1) / 4, which gives square roots because p = 3 (mod 4)
+const SQRT_EXPONENT: [u64; 4] = [
+    0xffff_ffff_bfff_ff0c,
+    0xffff_ffff_ffff_ffff,
+    0xffff_ffff_ffff_ffff,
+    0x3fff_ffff_ffff_ffff,
+];
+
+/// An element of the secp256k1 base field, stored as four 64-bit limbs on the stack.
+///
+/// Unlike FieldElement, it allocates nothing and doesn't carry its prime, and its arithmetic
+/// takes the same sequence of steps for every input. Values are always fully reduced below p.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fe256([u64; 4]);
+
+impl Fe256 {
+    pub fn zero() -> Fe256 {
+        Fe256([0; 4])
+    }
+    pub fn one() -> Fe256 {
+        Fe256([1, 0, 0, 0])
+    }
+    pub fn from_u64(value: u64) -> Fe256 {
+        Fe256([value, 0, 0, 0])
+    }
+    /// Parses a big-endian 32-byte value, or returns None if it is not below p
+    pub fn from_bytes(bytes: &[u8; 32]) -> Option<Fe256> {
+        let mut limbs = [0u64; 4];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[32 - 8 * (i + 1)..32 - 8 * i]);
+            *limb = u64::from_be_bytes(word);
+        }
+        let (_, borrow) = sub_limbs(&limbs, &P);
+        if !borrow {
+            return None;
+        }
+        Some(Fe256(limbs))
+    }
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, limb) in self.0.iter().enumerate() {
+            bytes[32 - 8 * (i + 1)..32 - 8 * i].copy_from_slice(&limb.to_be_bytes());
+        }
+        bytes
+    }
+    /// Converts from a BigUint, reducing it mod p
+    pub fn from_biguint(value: &BigUint) -> Fe256 {
+        let p = BigUint::from_bytes_be(&Fe256(P).to_bytes());
+        let bytes = (value % p).to_bytes_be();
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+        Fe256::from_bytes(&padded).unwrap()
+    }
+    pub fn to_biguint(&self) -> BigUint {
+        BigUint::from_bytes_be(&self.to_bytes())
+    }
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().fold(0, |acc, limb| acc | limb) == 0
+    }
+    pub fn is_odd(&self) -> bool {
+        self.0[0] & 1 == 1
+    }
+    pub fn square(&self) -> Fe256 {
+        *self * *self
+    }
+    /// Raises self to a power given as little-endian limbs
+    pub fn pow(&self, exponent: &[u64; 4]) -> Fe256 {
+        let mut result = Fe256::one();
+        for limb in exponent.iter().rev() {
+            for bit in (0..64).rev() {
+                result = result.square();
+                let product = result * *self;
+                result = select(&product, &result, (limb >> bit) & 1 == 1);
+            }
+        }
+        result
+    }
+    /// Returns the inverse, or None for zero
+    pub fn invert(&self) -> Option<Fe256> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.pow(&P_MINUS_2))
+    }
+    /// Returns a square root, or None if self is not a square
+    pub fn sqrt(&self) -> Option<Fe256> {
+        let root = self.pow(&SQRT_EXPONENT);
+        if root.square() == *self {
+            Some(root)
+        } else {
+            None
+        }
+    }
+}
+
+// Returns a if choice is true and b otherwise, by masking rather than branching
+fn select(a: &Fe256, b: &Fe256, choice: bool) -> Fe256 {
+    let mask = (choice as u64).wrapping_neg();
+    let mut limbs = [0u64; 4];
+    for (i, limb) in limbs.iter_mut().enumerate() {
+        *limb = b.0[i] ^ ((a.0[i] ^ b.0[i]) & mask);
+    }
+    Fe256(limbs)
+}
+
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut sum = [0u64; 4];
+    let mut carry = false;
+    for i in 0..4 {
+        let (s, c1) = a[i].overflowing_add(b[i]);
+        let (s, c2) = s.overflowing_add(carry as u64);
+        sum[i] = s;
+        carry = c1 | c2;
+    }
+    (sum, carry)
+}
+
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], bool) {
+    let mut difference = [0u64; 4];
+    let mut borrow = false;
+    for i in 0..4 {
+        let (d, b1) = a[i].overflowing_sub(b[i]);
+        let (d, b2) = d.overflowing_sub(borrow as u64);
+        difference[i] = d;
+        borrow = b1 | b2;
+    }
+    (difference, borrow)
+}
+
+// Subtracts p from a value below 2p when the value is at least p. Adding C instead of subtracting
+// p gives the same result mod 2**256, and carries out exactly when the value is at least p.
+fn reduce_once(limbs: [u64; 4], overflow: bool) -> Fe256 {
+    let (reduced, carry) = add_limbs(&limbs, &[C, 0, 0, 0]);
+    select(&Fe256(reduced), &Fe256(limbs), overflow | carry)
+}
+
+impl std::ops::Add for Fe256 {
+    type Output = Fe256;
+    fn add(self, other: Fe256) -> Fe256 {
+        let (sum, carry) = add_limbs(&self.0, &other.0);
+        reduce_once(sum, carry)
+    }
+}
+
+impl std::ops::Sub for Fe256 {
+    type Output = Fe256;
+    fn sub(self, other: Fe256) -> Fe256 {
+        let (difference, borrow) = sub_limbs(&self.0, &other.0);
+        // On a borrow the result wrapped around by 2**256, and adding p means subtracting C
+        let (corrected, _) = sub_limbs(&difference, &masked_c(borrow));
+        Fe256(corrected)
+    }
+}
+
+impl std::ops::Mul for Fe256 {
+    type Output = Fe256;
+    fn mul(self, other: Fe256) -> Fe256 {
+        // Schoolbook multiplication into eight limbs
+        let mut product = [0u64; 8];
+        for i in 0..4 {
+            let mut carry = 0u128;
+            for j in 0..4 {
+                let t = product[i + j] as u128 + self.0[i] as u128 * other.0[j] as u128 + carry;
+                product[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            product[i + 4] = carry as u64;
+        }
+        // Fold the high half in as high * C, leaving a fifth limb below 2**34
+        let mut folded = [0u64; 4];
+        let mut carry = 0u128;
+        for i in 0..4 {
+            let t = product[i] as u128 + product[i + 4] as u128 * C as u128 + carry;
+            folded[i] = t as u64;
+            carry = t >> 64;
+        }
+        // Fold the fifth limb the same way. This can carry out once more, but then the low limbs
+        // are tiny, so adding C for that carry can't overflow again
+        let (folded, overflow) = add_limbs(&folded, &u128_limbs(carry * C as u128));
+        let (folded, _) = add_limbs(&folded, &masked_c(overflow));
+        reduce_once(folded, false)
+    }
+}
+
+// Returns C as limbs if choice is true and zero otherwise
+fn masked_c(choice: bool) -> [u64; 4] {
+    [C & (choice as u64).wrapping_neg(), 0, 0, 0]
+}
+
+fn u128_limbs(value: u128) -> [u64; 4] {
+    [value as u64, (value >> 64) as u64, 0, 0]
+}
+
+impl std::ops::Neg for Fe256 {
+    type Output = Fe256;
+    fn neg(self) -> Fe256 {
+        Fe256::zero() - self
+    }
+}
+
+impl Field for Fe256 {
+    fn zero(&self) -> Fe256 {
+        Fe256::zero()
+    }
+    fn one(&self) -> Fe256 {
+        Fe256::one()
+    }
+    fn element_from_integer(&self, value: &BigUint) -> Fe256 {
+        Fe256::from_biguint(value)
+    }
+    fn to_integer(&self) -> BigUint {
+        self.to_biguint()
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        Fe256::to_bytes(self).to_vec()
+    }
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<Fe256> {
+        let mut array = [0u8; 32];
+        if bytes.len() != 32 {
+            return None;
+        }
+        array.copy_from_slice(bytes);
+        Fe256::from_bytes(&array)
+    }
+    fn is_zero(&self) -> bool {
+        Fe256::is_zero(self)
+    }
+    fn is_odd(&self) -> bool {
+        Fe256::is_odd(self)
+    }
+    fn squared(&self) -> Fe256 {
+        self.square()
+    }
+    fn invert(&self) -> Option<Fe256> {
+        Fe256::invert(self)
+    }
+    fn sqrt(&self) -> Option<Fe256> {
+        Fe256::sqrt(self)
+    }
+    fn conditional_swap(&mut self, other: &mut Fe256, choice: bool) {
+        let swapped = select(other, self, choice);
+        *other = select(self, other, choice);
+        *self = swapped;
+    }
+}
+
+impl fmt::Display for Fe256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.to_bytes().iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
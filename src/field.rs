@@ -0,0 +1,76 @@
+use num_bigint::BigUint;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// The operations the curve code needs from the field its coordinates live in. EllipticCurve and
+/// CurvePoint are generic over it, so fixed-width, Montgomery or instrumented fields can be used
+/// without touching the point formulas.
+///
+/// FieldElement keeps its prime in every element, so constants and conversions go through an
+/// existing element of the same field rather than from nothing. Method names avoid FieldElement's
+/// older in-place add and square, which would otherwise shadow or be shadowed by these.
+pub trait Field:
+    Clone
+    + PartialEq
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Returns the additive identity of the field self belongs to
+    fn zero(&self) -> Self;
+    /// Returns the multiplicative identity of the field self belongs to
+    fn one(&self) -> Self;
+    /// Returns value (mod p) as an element of the field self belongs to
+    fn element_from_integer(&self, value: &BigUint) -> Self;
+    fn to_integer(&self) -> BigUint;
+    /// Serializes the value as a big-endian integer, padded to the byte length of the modulus
+    fn to_bytes(&self) -> Vec<u8>;
+    /// Parses the output of to_bytes as an element of the field self belongs to. Returns None if
+    /// the length is wrong or the value is not below the modulus.
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<Self>;
+    fn is_zero(&self) -> bool;
+    fn is_odd(&self) -> bool;
+    fn squared(&self) -> Self;
+    /// Returns the multiplicative inverse, or None for zero
+    fn invert(&self) -> Option<Self>;
+    /// Returns a square root, or None if self is not a square
+    fn sqrt(&self) -> Option<Self>;
+    /// Swaps self and other if choice is true, without branching on choice
+    fn conditional_swap(&mut self, other: &mut Self, choice: bool);
+    /// Replaces every nonzero element with its inverse, leaving zeros as they are. Uses
+    /// Montgomery's trick: one inversion of the product of all the elements, then 3(n - 1)
+    /// multiplications to peel off each inverse.
+    fn batch_invert(elements: &mut [Self]) {
+        // products[i] is the product of the first i + 1 nonzero elements
+        let mut products: Vec<Self> = Vec::with_capacity(elements.len());
+        for element in elements.iter().filter(|element| !element.is_zero()) {
+            let product = match products.last() {
+                Some(last) => last.clone() * element.clone(),
+                None => element.clone(),
+            };
+            products.push(product);
+        }
+        let mut inverse = match products.pop() {
+            Some(product) => product.invert().unwrap(),
+            None => return,
+        };
+        // Walking backwards, inverse is 1 / (e_1 * ... * e_i) for the current element e_i, so its
+        // inverse is inverse * (e_1 * ... * e_(i - 1)), and inverse * e_i moves on to e_(i - 1)
+        for element in elements
+            .iter_mut()
+            .rev()
+            .filter(|element| !element.is_zero())
+        {
+            match products.pop() {
+                Some(product) => {
+                    let next = inverse.clone() * element.clone();
+                    *element = inverse * product;
+                    inverse = next;
+                }
+                None => *element = inverse.clone(),
+            }
+        }
+    }
+}
@@ -1,9 +1,16 @@
+use crate::encoding::{parse_hex, to_fixed_bytes};
+use crate::error::Error;
+use crate::field::Field;
 use num_bigint::{BigUint, ToBigInt, ToBigUint};
-// use num_integer::Integer;
+use num_integer::Integer;
 use std::fmt;
 // use std::ops::{Div, Sub, Rem};
 // use std::cmp::PartialOrd;
 // use num_integer::Integer;
+/// An integer modulo a prime, backed by BigUint.
+///
+/// BigUint arithmetic is not constant time, so routines described as constant time only avoid
+/// branching on secret values. They don't hide timing differences inside the arithmetic itself.
 #[derive(Debug)]
 pub struct FieldElement {
     value: BigUint,
@@ -15,12 +22,28 @@ impl FieldElement {
         T: ToBigUint,
         U: ToBigUint,
     {
-        let v = value.to_biguint().unwrap();
-        let p = prime.to_biguint().unwrap();
-        FieldElement {
+        FieldElement::try_new(value, prime).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates value (mod prime). Fails if either argument is negative or the modulus is below 2.
+    /// The modulus is not checked for primality.
+    pub fn try_new<T, U>(value: T, prime: U) -> Result<FieldElement, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        let v = value.to_biguint().ok_or(Error::NegativeValue)?;
+        let p = prime.to_biguint().ok_or(Error::NegativeValue)?;
+        if p < 2.to_biguint().unwrap() {
+            return Err(Error::InvalidModulus);
+        }
+        Ok(FieldElement {
             value: v % &p,
             prime: p,
-        }
+        })
+    }
+    /// Creates an element from big-endian hex strings for the value and the prime
+    pub fn try_from_hex(value: &[u8], prime: &[u8]) -> Result<FieldElement, Error> {
+        FieldElement::try_new(parse_hex(value)?, parse_hex(prime)?)
     }
     pub fn from(other: &FieldElement) -> FieldElement {
         FieldElement {
@@ -29,11 +52,58 @@ impl FieldElement {
         }
     }
     pub fn add(&mut self, other: &FieldElement) {
+        *self = self.try_add(other).unwrap_or_else(|e| panic!("{}", e));
+    }
+    // The operator impls accept any integer on the right hand side, so they can't tell an element
+    // of another field from a plain integer. These checked forms reject mismatched fields instead.
+    pub fn try_add(&self, other: &FieldElement) -> Result<FieldElement, Error> {
+        self.check_prime(other)?;
+        Ok(self + other)
+    }
+    pub fn try_sub(&self, other: &FieldElement) -> Result<FieldElement, Error> {
+        self.check_prime(other)?;
+        Ok(self - other)
+    }
+    pub fn try_mul(&self, other: &FieldElement) -> Result<FieldElement, Error> {
+        self.check_prime(other)?;
+        Ok(self * other)
+    }
+    /// Divides by other, failing if the primes differ or other is zero
+    pub fn try_div(&self, other: &FieldElement) -> Result<FieldElement, Error> {
+        self.check_prime(other)?;
+        if other.value == 0.to_biguint().unwrap() {
+            return Err(Error::DivisionByZero);
+        }
+        Ok(self / other)
+    }
+    fn check_prime(&self, other: &FieldElement) -> Result<(), Error> {
         if self.prime != other.prime {
-            panic!("Primes don't match!");
+            return Err(Error::MismatchedPrimes);
         }
-        self.value += &other.value;
-        self.value %= &self.prime;
+        Ok(())
+    }
+    /// Swaps the values of self and other if choice is true, using a mask rather than a branch on
+    /// choice.
+    pub fn conditional_swap(&mut self, other: &mut FieldElement, choice: bool) {
+        let ones = (1.to_biguint().unwrap() << self.prime.bits()) - 1.to_biguint().unwrap();
+        let mask = ones * BigUint::from(choice as u8);
+        let t = (&self.value ^ &other.value) & mask;
+        self.value ^= &t;
+        other.value ^= &t;
+    }
+    /// Inverts every element in place with a single inversion and 3(n - 1) multiplications,
+    /// leaving zeros unchanged. Panics if the elements don't all share a prime.
+    pub fn batch_invert(elements: &mut [FieldElement]) {
+        // The operators ignore the prime on the right hand side, so a mismatch would otherwise go
+        // unnoticed and give wrong inverses
+        if let Some((first, rest)) = elements.split_first() {
+            for element in rest {
+                first
+                    .check_prime(element)
+                    .unwrap_or_else(|e| panic!("{}", e));
+            }
+        }
+        <FieldElement as Field>::batch_invert(elements)
     }
     pub fn zero_out(&mut self) {
         self.value = 0.to_biguint().unwrap();
@@ -71,6 +141,161 @@ impl FieldElement {
             .modpow(&reduced_expt.to_biguint().unwrap(), &self.prime);
         FieldElement::new(new_value, self.prime.clone())
     }
+    /// Returns true if the element has a square root in the field. By Euler's criterion, a nonzero
+    /// a is a square exactly when a**((p - 1) / 2) = 1 (mod p)
+    pub fn is_square(&self) -> bool {
+        let one = 1.to_biguint().unwrap();
+        if self.value == 0.to_biguint().unwrap() {
+            return true;
+        }
+        self.value.modpow(&((&self.prime - &one) >> 1), &self.prime) == one
+    }
+    /// Returns the multiplicative inverse, or None if there is none: for zero, or for a value sharing
+    /// a factor with a modulus that isn't prime. Uses the extended Euclidean algorithm, which runs
+    /// in variable time, so prefer invert_constant_time for secret values.
+    pub fn invert(&self) -> Option<FieldElement> {
+        let zero = 0.to_bigint().unwrap();
+        let prime = self.prime.to_bigint().unwrap();
+        // Invariant: r0 = t0 * value and r1 = t1 * value (mod p)
+        let (mut r0, mut r1) = (prime.clone(), self.value.to_bigint().unwrap());
+        let (mut t0, mut t1) = (zero.clone(), 1.to_bigint().unwrap());
+        while r1 != zero {
+            let (q, r2) = r0.div_rem(&r1);
+            let t2 = &t0 - &q * &t1;
+            r0 = std::mem::replace(&mut r1, r2);
+            t0 = std::mem::replace(&mut t1, t2);
+        }
+        // r0 is now gcd(p, value)
+        if r0 != 1.to_bigint().unwrap() {
+            return None;
+        }
+        let inverse = t0.mod_floor(&prime).to_biguint().unwrap();
+        Some(FieldElement::new(inverse, self.prime.clone()))
+    }
+    /// Returns the same result as invert using the safegcd algorithm of Bernstein and Yang, which
+    /// runs a fixed number of steps for a given modulus and doesn't branch on the value.
+    pub fn invert_constant_time(&self) -> Option<FieldElement> {
+        // safegcd needs an odd modulus. The only even prime is 2, where there is nothing to hide
+        if self.prime.is_even() {
+            return self.invert();
+        }
+        let one = 1.to_bigint().unwrap();
+        let prime = self.prime.to_bigint().unwrap();
+        // Enough steps to reach g = 0 for any value below p (Theorem 11.2 of the paper)
+        let bits = self.prime.bits() as i64;
+        let steps = if bits < 46 {
+            (49 * bits + 80) / 17
+        } else {
+            (49 * bits + 57) / 17
+        };
+        // Invariants: f = u * value and g = v * value (mod p), f is odd, and gcd(f, g) stays
+        // gcd(p, value) up to sign
+        let (mut f, mut g) = (prime.clone(), self.value.to_bigint().unwrap());
+        let (mut u, mut v) = (0.to_bigint().unwrap(), one.clone());
+        let mut delta: i64 = 1;
+        for _ in 0..steps {
+            // If delta > 0 and g is odd, replace (delta, f, g, u, v) with (-delta, g, -f, v, -u)
+            let swap = (-delta >> 63) & (g.is_odd() as i64);
+            let choice = swap.to_bigint().unwrap();
+            let sign = (1 - 2 * swap).to_bigint().unwrap();
+            let t = (&g - &f) * &choice;
+            f += &t;
+            g = (g - t) * &sign;
+            let t = (&v - &u) * &choice;
+            u += &t;
+            v = ((v - t) * &sign).mod_floor(&prime);
+            delta = delta * (1 - 2 * swap) + 1;
+            // Then add f to g if g is odd, which makes it even, and halve it
+            let odd = (g.is_odd() as u8).to_bigint().unwrap();
+            g = (g + &f * &odd) >> 1;
+            v = (v + &u * &odd).mod_floor(&prime);
+            let odd = (v.is_odd() as u8).to_bigint().unwrap();
+            v = (v + &prime * odd) >> 1;
+        }
+        // g is now 0 and f = +/- gcd(p, value), so the value is invertible exactly when f = +/- 1,
+        // and then its inverse is u * f
+        if &f * &f != one {
+            return None;
+        }
+        let inverse = (u * f).mod_floor(&prime).to_biguint().unwrap();
+        Some(FieldElement::new(inverse, self.prime.clone()))
+    }
+    /// Returns a square root of the element, or None if it isn't a square. The other root is p - root.
+    /// The modulus is assumed prime. Over a composite one this still returns, giving either a true
+    /// root or None.
+    pub fn sqrt(&self) -> Option<FieldElement> {
+        let one = 1.to_biguint().unwrap();
+        // 0 and 1 are their own roots. This also covers every element of F_2.
+        if self.value <= one {
+            return Some(FieldElement::from(self));
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let four = 4.to_biguint().unwrap();
+        let root = if &self.prime % &four == 3.to_biguint().unwrap() {
+            // If p = 3 (mod 4), then (p + 1) / 4 is an integer, and for a square a = b**2,
+            // a**((p + 1) / 4) = b**((p + 1) / 2) = b * b**((p - 1) / 2) = +/- b by Fermat's little theorem
+            self.pow((&self.prime + &one) >> 2)
+        } else {
+            self.tonelli_shanks()?
+        };
+        // Both formulas rely on p being prime, and give a wrong root otherwise
+        if &root * &root != *self {
+            return None;
+        }
+        Some(root)
+    }
+    // General square root for odd primes, assuming self is a nonzero square. Returns None when a
+    // composite modulus breaks the invariants, instead of looping forever.
+    fn tonelli_shanks(&self) -> Option<FieldElement> {
+        let one = FieldElement::new(1, self.prime.clone());
+        // Write p - 1 = q * 2**s with q odd
+        let mut q = &self.prime - 1.to_biguint().unwrap();
+        let mut s = 0;
+        while q.is_even() {
+            q >>= 1;
+            s += 1;
+        }
+        // Any non-square z works. Half of the field is non-square, so this terminates quickly
+        let mut z = FieldElement::new(2, self.prime.clone());
+        while z.is_square() {
+            z += 1;
+            if z.value == 0.to_biguint().unwrap() {
+                return None;
+            }
+        }
+
+        // Invariants: r**2 = self * t, and t**(2**(m - 1)) = 1, where c generates the subgroup of
+        // order 2**m. Each iteration shrinks the order of t until t = 1 and r is the root
+        let mut m = s;
+        let mut c = z.pow(q.clone());
+        let mut t = self.pow(q.clone());
+        let mut r = self.pow((q + 1.to_biguint().unwrap()) >> 1);
+        while t != one {
+            // Find the least i with t**(2**i) = 1
+            let mut i = 0;
+            let mut t_squared = FieldElement::from(&t);
+            while t_squared != one {
+                t_squared.square();
+                i += 1;
+                // Over a prime i stays below m, so the order of t shrinks with every iteration
+                if i >= m {
+                    return None;
+                }
+            }
+            // b = c**(2**(m - i - 1))
+            let mut b = c;
+            for _ in 0..(m - i - 1) {
+                b.square();
+            }
+            m = i;
+            c = &b * &b;
+            t *= &c;
+            r *= &b;
+        }
+        Some(r)
+    }
 }
 
 impl ToBigUint for FieldElement {
@@ -85,9 +310,59 @@ impl ToBigUint for &FieldElement {
     }
 }
 
+impl Field for FieldElement {
+    fn zero(&self) -> FieldElement {
+        FieldElement::new(0, self.prime.clone())
+    }
+    fn one(&self) -> FieldElement {
+        FieldElement::new(1, self.prime.clone())
+    }
+    fn element_from_integer(&self, value: &BigUint) -> FieldElement {
+        FieldElement::new(value.clone(), self.prime.clone())
+    }
+    fn to_integer(&self) -> BigUint {
+        self.value.clone()
+    }
+    fn to_bytes(&self) -> Vec<u8> {
+        to_fixed_bytes(&self.value, self.prime.bits().div_ceil(8)).unwrap()
+    }
+    fn element_from_bytes(&self, bytes: &[u8]) -> Option<FieldElement> {
+        let value = BigUint::from_bytes_be(bytes);
+        if bytes.len() != self.prime.bits().div_ceil(8) || value >= self.prime {
+            return None;
+        }
+        Some(FieldElement::new(value, self.prime.clone()))
+    }
+    fn is_zero(&self) -> bool {
+        self.value == 0.to_biguint().unwrap()
+    }
+    fn is_odd(&self) -> bool {
+        self.value.is_odd()
+    }
+    fn squared(&self) -> FieldElement {
+        self * self
+    }
+    fn invert(&self) -> Option<FieldElement> {
+        FieldElement::invert(self)
+    }
+    fn sqrt(&self) -> Option<FieldElement> {
+        FieldElement::sqrt(self)
+    }
+    fn conditional_swap(&mut self, other: &mut FieldElement, choice: bool) {
+        FieldElement::conditional_swap(self, other, choice)
+    }
+}
+
+impl std::ops::Neg for FieldElement {
+    type Output = FieldElement;
+    fn neg(self) -> FieldElement {
+        FieldElement::new(0, self.prime.clone()) - self
+    }
+}
+
 impl Clone for FieldElement {
     fn clone(&self) -> FieldElement {
-        FieldElement::new(self.prime.clone(), self.value.clone())
+        FieldElement::new(self.value.clone(), self.prime.clone())
     }
 }
 
@@ -103,7 +378,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn add(self: Self, other: Rhs) -> FieldElement {
+    fn add(self, other: Rhs) -> FieldElement {
         FieldElement::new(self.value + other.to_biguint().unwrap(), self.prime)
     }
 }
@@ -123,7 +398,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn add(self: Self, other: Rhs) -> FieldElement {
+    fn add(self, other: Rhs) -> FieldElement {
         FieldElement::new(
             self.value.clone() + other.to_biguint().unwrap(),
             self.prime.clone(),
@@ -136,7 +411,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn mul(self: Self, other: Rhs) -> FieldElement {
+    fn mul(self, other: Rhs) -> FieldElement {
         FieldElement::new(
             self.value.clone() * other.to_biguint().unwrap(),
             self.prime.clone(),
@@ -149,7 +424,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn mul(self: Self, other: Rhs) -> FieldElement {
+    fn mul(self, other: Rhs) -> FieldElement {
         FieldElement::new(
             self.value.clone() * other.to_biguint().unwrap(),
             self.prime.clone(),
@@ -162,7 +437,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn sub(self: Self, other: Rhs) -> FieldElement {
+    fn sub(self, other: Rhs) -> FieldElement {
         let other = other.to_biguint().unwrap() % &self.prime;
         if self.value < other {
             return FieldElement::new((self.value + &self.prime) - other, self.prime.clone());
@@ -176,7 +451,7 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn sub(self: Self, other: Rhs) -> FieldElement {
+    fn sub(self, other: Rhs) -> FieldElement {
         let other = other.to_biguint().unwrap() % &self.prime;
         if self.value < other {
             return FieldElement::new((&self.value + &self.prime) - other, self.prime.clone());
@@ -204,10 +479,9 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn div(self: Self, other: Rhs) -> FieldElement {
-        let two: BigUint = 2.to_biguint().unwrap();
-        let inverse = FieldElement::new(other, self.prime.clone()).pow(&self.prime - two);
-        &self * inverse
+    /// Panics if other is zero
+    fn div(self, other: Rhs) -> FieldElement {
+        &self / other
     }
 }
 
@@ -216,10 +490,15 @@ where
     Rhs: ToBigUint,
 {
     type Output = FieldElement;
-    fn div(self: Self, other: Rhs) -> FieldElement {
-        let two: BigUint = 2.to_biguint().unwrap();
-        let inverse = FieldElement::new(other, self.prime.clone()).pow(&self.prime - two);
-        self * inverse
+    /// Panics if other is zero
+    fn div(self, other: Rhs) -> FieldElement {
+        let inverse = FieldElement::new(other, self.prime.clone())
+            .invert()
+            .unwrap_or_else(|| panic!("{}", Error::DivisionByZero));
+        FieldElement {
+            value: &self.value * inverse.value % &self.prime,
+            prime: self.prime.clone(),
+        }
     }
 }
 
@@ -228,9 +507,7 @@ where
     Rhs: ToBigUint,
 {
     fn div_assign(&mut self, other: Rhs) {
-        let two: BigUint = 2.to_biguint().unwrap();
-        let inverse = FieldElement::new(other, self.prime.clone()).pow(&self.prime - two);
-        self.value = &self.value * inverse.value % &self.prime;
+        self.value = (&*self / other).value;
     }
 }
 
@@ -0,0 +1,15 @@
+extern crate num_integer;
+pub mod address;
+pub mod curves;
+pub mod ecdsa;
+pub mod elliptic_curve;
+pub mod encoding;
+pub mod error;
+pub mod fe256;
+pub mod field;
+pub mod finite_field;
+pub mod hash;
+pub mod message;
+pub mod rfc6979;
+pub mod scalar;
+pub mod schnorr;
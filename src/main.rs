@@ -1,35 +1,50 @@
-use num_bigint::{BigUint, ToBigUint};
-extern crate num_integer;
+#[cfg(test)]
+use bitcoin::address::Address;
+use bitcoin::curves::secp256k1;
+use bitcoin::ecdsa::Signature;
+#[cfg(test)]
+use bitcoin::ecdsa::{recover_public_key, PrivateKey, PublicKey};
+use bitcoin::elliptic_curve::CurvePoint;
+#[cfg(test)]
+use bitcoin::elliptic_curve::EllipticCurve;
+#[cfg(test)]
+use bitcoin::encoding::{
+    base58check_decode, base64_decode, base64_encode, bech32_decode, bech32_encode,
+};
+#[cfg(test)]
+use bitcoin::error::{DerError, Error};
+#[cfg(test)]
+use bitcoin::fe256::Fe256;
+#[cfg(test)]
+use bitcoin::field::Field;
+#[cfg(test)]
+use bitcoin::finite_field::FieldElement;
+use bitcoin::hash::MessageHash;
+#[cfg(test)]
+use bitcoin::hash::{hash160, hash256, hmac_sha256, ripemd160, sha256};
+#[cfg(test)]
+use bitcoin::message::{sign_message, verify_message, AddressType};
+#[cfg(test)]
+use bitcoin::rfc6979::generate_k;
+#[cfg(test)]
+use bitcoin::scalar::Scalar;
+#[cfg(test)]
+use bitcoin::schnorr::{self, XOnlyPublicKey};
+#[cfg(test)]
+use num_bigint::BigUint;
 // use num_integer::Integer;
-mod elliptic_curve;
-mod finite_field;
-use elliptic_curve::{CurvePoint, EllipticCurve};
-use finite_field::FieldElement;
 // use finiteField;
 
+#[allow(non_snake_case)]
 fn main() {
-    let S256 = EllipticCurve::new_large(
-        b"0",
-        b"7",
-        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
-    );
-    let G = CurvePoint::new_large(
-        b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
-        b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
-        &S256,
-    );
-    let N = BigUint::parse_bytes(
-        b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
-        16,
-    )
-    .unwrap();
+    let S256 = secp256k1().get_curve();
     // let z = BigUint::parse_bytes(b"bc62d4b80d9e36da29c16c5d4d9f11731f36052c72401a76c23c0fb5a9b74423", 16).unwrap();
     // let r = BigUint::parse_bytes(b"37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6", 16).unwrap();
     // let s = BigUint::parse_bytes(b"8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec", 16).unwrap();
     let point = CurvePoint::new_large(
         b"887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c",
         b"61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34",
-        &S256,
+        S256,
     );
     // // println!("{:?}", G * n);
     // let s_inv = s.modpow(&(&N - 2.to_biguint().unwrap()), &N); //pow(s, N-2, N)
@@ -38,11 +53,15 @@ fn main() {
     println!(
         "{}",
         point.verify_signature(
-            b"7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d",
-            b"eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c",
-            b"c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6",
-            &G,
-            &N
+            &MessageHash::from_hex(
+                b"7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d"
+            )
+            .unwrap(),
+            &Signature::from_hex(
+                b"eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c",
+                b"c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6",
+            )
+            .unwrap(),
         )
     );
 
@@ -167,59 +186,1618 @@ fn test_curve_mul() {
 }
 
 #[test]
+fn test_curve_group() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let generator = curve.get_generator().unwrap();
+    assert_eq!(generator, CurvePoint::new(15, 86, &curve));
+    assert_eq!(curve.get_order(), Some(&BigUint::from(7u8)));
+    assert_eq!(curve.get_cofactor(), Some(&BigUint::from(36u8)));
+    assert_eq!(&generator * 7, CurvePoint::infinity(&curve));
+    assert_eq!(EllipticCurve::new(0, 7, 223).get_generator(), None);
+
+    // Scalars are reduced by the number of points, 7 * 36 = 252, which preserves the result even
+    // for points outside the subgroup generated by G
+    let p1 = CurvePoint::new(192, 105, &curve);
+    assert_eq!(&p1 * 257, &p1 * 5);
+    assert_ne!(&p1 * 5, &p1 * 12);
+    let p2 = CurvePoint::new(47, 71, &curve);
+    assert_eq!(&p2 * 21, CurvePoint::infinity(&curve));
+}
+
+#[test]
+#[should_panic]
+fn test_curve_group_wrong_order() {
+    EllipticCurve::new(0, 7, 223).with_group(15, 86, 5, 1);
+}
+
+#[test]
+fn test_curve_group_validation() {
+    let curve = EllipticCurve::new(0, 7, 223);
+    // 0 * G and 21 * (47, 71) are both infinity, but neither 0 nor 21 is a prime order
+    for (x, y, order) in [(15, 86, 0), (15, 86, 1), (47, 71, 21)].iter() {
+        assert_eq!(
+            curve
+                .clone()
+                .try_with_group(*x, *y, *order, 12)
+                .unwrap_err(),
+            Error::InvalidGroupOrder
+        );
+    }
+    // The curve has 252 = 7 * 36 points. 7 is far outside the Hasse bound [195, 253], while
+    // 7 * 35 = 245 is within it but doesn't annihilate the other points.
+    for cofactor in [1, 35].iter() {
+        assert_eq!(
+            curve
+                .clone()
+                .try_with_group(15, 86, 7, *cofactor)
+                .unwrap_err(),
+            Error::InvalidCofactor
+        );
+    }
+    assert!(curve.try_with_group(15, 86, 7, 36).is_ok());
+}
+
+#[test]
+#[allow(non_snake_case)]
 fn test_secp256k1() {
-    let S256 = EllipticCurve::new_large(
+    let secp = secp256k1();
+    assert_eq!(secp.get_name(), "secp256k1");
+    let bare = EllipticCurve::new_large(
         b"0",
         b"7",
         b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
     );
-    let G = CurvePoint::new_large(
-        b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
-        b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
-        &S256,
+    assert_eq!(secp.get_curve().get_prime(), bare.get_prime());
+    assert_eq!(secp.get_curve().get_a(), bare.get_a());
+    assert_eq!(secp.get_curve().get_b(), bare.get_b());
+    // The same equation without the group is a different curve
+    assert_ne!(secp.get_curve(), &bare);
+    assert_eq!(
+        secp.get_generator(),
+        CurvePoint::new_large(
+            b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            secp.get_curve(),
+        )
     );
+    assert_eq!(secp.get_cofactor(), &BigUint::from(1u8));
+    let G = secp.get_generator();
     let n = BigUint::parse_bytes(
         b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
         16,
     )
     .unwrap();
-    assert_eq!(G * n, CurvePoint::infinity(&S256));
+    assert_eq!(secp.get_order(), &n);
+    assert_eq!(&G * &n, CurvePoint::infinity(secp.get_curve()));
+    // (n - 1) * G = -G, so adding G gives the point at infinity
+    assert_eq!(&G * (&n - 1u8) + G, CurvePoint::infinity(secp.get_curve()));
+    // Repeated lookups share the same parameters
+    assert!(std::ptr::eq(secp, secp256k1()));
 }
 
 #[test]
+#[allow(non_snake_case)]
 fn test_validate_signature() {
-    let S256 = EllipticCurve::new_large(
-        b"0",
-        b"7",
-        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+    let S256 = secp256k1().get_curve();
+    let point = CurvePoint::new_large(
+        b"887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c",
+        b"61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34",
+        S256,
+    );
+    assert!(point.verify_signature(
+        &MessageHash::from_hex(b"ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60")
+            .unwrap(),
+        &Signature::from_hex(
+            b"ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395",
+            b"68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4",
+        )
+        .unwrap(),
+    ));
+    assert!(point.verify_signature(
+        &MessageHash::from_hex(b"7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d")
+            .unwrap(),
+        &Signature::from_hex(
+            b"eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c",
+            b"c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6",
+        )
+        .unwrap(),
+    ));
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_sign_with_nonce() {
+    let S256 = secp256k1().get_curve();
+    let key = PrivateKey::new(12345, S256);
+    assert_eq!(
+        key.get_public_key().get_point(),
+        &CurvePoint::new_large(
+            b"f01d6b9018ab421dd410404cb869072065522bf85734008f105cf385a023a80f",
+            b"0eba29d0f0c5408ed681984dc525982abefccd9f7ff01dd26da4999cf3f6a295",
+            S256,
+        )
+    );
+    let n = S256.get_order().unwrap();
+    let z =
+        MessageHash::from_hex(b"969f6056aa26f7d2795fd013fe88868d09c9f6aed96965016e1936ae47060d48")
+            .unwrap();
+    let sig = key
+        .sign_with_nonce(&z, &Scalar::new(1234567890, n))
+        .unwrap();
+    assert_eq!(
+        sig,
+        Signature::new(
+            BigUint::parse_bytes(
+                b"2b698a0f0a4041b77e63488ad48c23e8e8838dd1fb7520408b121697b782ef22",
+                16
+            )
+            .unwrap(),
+            BigUint::parse_bytes(
+                b"1dbc63bfef4416705e602a7b564161167076d8b20990a0f26f316cff2cb0bc1a",
+                16
+            )
+            .unwrap(),
+        )
+    );
+    assert_eq!(key.sign_with_nonce(&z, &Scalar::new(0, n)), None);
+}
+
+#[test]
+fn test_sign_roundtrip() {
+    let secp = secp256k1();
+    let key = PrivateKey::new(8675309, secp.get_curve());
+    let z =
+        MessageHash::from_hex(b"ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60")
+            .unwrap();
+    let sig = key.sign(&z);
+    assert_eq!(sig.verify(key.get_public_key(), &z), Ok(true));
+    let other = MessageHash::from_hex(b"1234").unwrap();
+    assert_eq!(sig.verify(key.get_public_key(), &other), Ok(false));
+}
+
+#[test]
+fn test_verify_against_infinity() {
+    let secp = secp256k1();
+    let n = secp.get_order();
+    let z = MessageHash::sha256(b"forged");
+    // r = x(kG) and s = z / k verify against the point at infinity unless it is rejected
+    let k = Scalar::new(12345, n);
+    let r = Scalar::new(secp.mul_generator(&k).get_x().get_value().clone(), n);
+    let s = Scalar::from_bytes_reduced(z.as_bytes(), n) * k.invert().unwrap();
+    let forged = Signature::new(r.get_value().clone(), s.get_value().clone());
+    let infinity = CurvePoint::infinity(secp.get_curve());
+    assert!(!infinity.verify_signature(&z, &forged));
+    assert!(!infinity.verify_signature_strict(&z, &forged));
+    assert_eq!(
+        forged.verify(&PublicKey::new(infinity), &z),
+        Err(Error::PointAtInfinity)
+    );
+}
+
+#[test]
+fn test_private_key_debug() {
+    let key = PrivateKey::new(8675309, secp256k1().get_curve());
+    let debug = format!("{:?}", key);
+    assert!(debug.starts_with("PrivateKey { public_key: "));
+    assert!(!debug.contains(&format!("{:?}", key.get_secret())));
+    assert!(!debug.contains("secret"));
+}
+
+#[test]
+fn test_recover_public_key() {
+    let curve = secp256k1().get_curve();
+    for i in 1u32..6 {
+        let key = PrivateKey::new(BigUint::from_bytes_be(&sha256(&i.to_be_bytes())), curve);
+        let z = MessageHash::sha256(format!("message {}", i).as_bytes());
+        let (sig, recid) = key.sign_recoverable(&z);
+        assert_eq!(sig, key.sign(&z));
+        assert_eq!(
+            recover_public_key(&sig, recid, &z, curve).as_ref(),
+            Ok(key.get_public_key())
+        );
+        // The other parity gives a different key, or none at all
+        let other = recover_public_key(&sig, recid ^ 1, &z, curve);
+        assert_ne!(other.as_ref(), Ok(key.get_public_key()));
+    }
+
+    let key = PrivateKey::new(8675309, curve);
+    let z = MessageHash::sha256(b"recover me");
+    let (sig, _) = key.sign_recoverable(&z);
+    assert_eq!(
+        recover_public_key(&sig, 4, &z, curve),
+        Err(Error::InvalidRecoveryId(4))
+    );
+    // r + N is above p for secp256k1, so ids 2 and 3 only fit vanishingly rare signatures
+    assert_eq!(
+        recover_public_key(&sig, 2, &z, curve),
+        Err(Error::PointNotOnCurve)
+    );
+    let zero_r = Signature::new(0, sig.get_s().clone());
+    assert_eq!(
+        recover_public_key(&zero_r, 0, &z, curve),
+        Err(Error::ScalarOutOfRange)
+    );
+}
+
+#[cfg(test)]
+fn hex_to_bytes(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_sha256() {
+    assert_eq!(
+        sha256(b"").to_vec(),
+        hex_to_bytes("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855")
+    );
+    assert_eq!(
+        sha256(b"abc").to_vec(),
+        hex_to_bytes("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+    );
+    assert_eq!(
+        sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq").to_vec(),
+        hex_to_bytes("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+    );
+    // The remaining NIST examples: a two-block message and one million repetitions of "a"
+    assert_eq!(
+        sha256(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu").to_vec(),
+        hex_to_bytes("cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1")
+    );
+    assert_eq!(
+        sha256(&[b'a'; 1_000_000]).to_vec(),
+        hex_to_bytes("cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0")
+    );
+}
+
+#[test]
+fn test_ripemd160() {
+    // Test vectors from the RIPEMD-160 specification
+    let vectors: [(&[u8], &str); 6] = [
+        (b"", "9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+        (b"a", "0bdc9d2d256b3ee9daae347be6f4dc835a467ffe"),
+        (b"abc", "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+        (
+            b"message digest",
+            "5d0689ef49d2fae572b881b123a85ffa21595f36",
+        ),
+        (
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq",
+            "12a053384a9c0c88e405a06c27dcf49ada62eb2b",
+        ),
+        (
+            b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            "9b752e45573d4b39f4dbd3323cab82bf63326bfb",
+        ),
+    ];
+    for (message, digest) in vectors.iter() {
+        assert_eq!(ripemd160(message).to_vec(), hex_to_bytes(digest));
+    }
+    assert_eq!(
+        ripemd160(&[b'a'; 1_000_000]).to_vec(),
+        hex_to_bytes("52783243c1697bdbe16d37f97f68f08325dc1528")
+    );
+}
+
+#[test]
+fn test_hash256_and_hash160() {
+    assert_eq!(
+        hash256(b"").to_vec(),
+        hex_to_bytes("5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456")
+    );
+    assert_eq!(
+        hash256(b"hello").to_vec(),
+        hex_to_bytes("9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50")
+    );
+    // The genesis block header hashes to the genesis block hash, displayed in reverse byte order
+    let header = hex_to_bytes(
+        "0100000000000000000000000000000000000000000000000000000000000000000000003ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a29ab5f49ffff001d1dac2b7c",
     );
-    let G = CurvePoint::new_large(
-        b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
-        b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
-        &S256,
+    let mut block_hash = hash256(&header);
+    block_hash.reverse();
+    assert_eq!(
+        block_hash.to_vec(),
+        hex_to_bytes("000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f")
     );
-    let N = BigUint::parse_bytes(
+    // HASH160 of the compressed public key for secret 1, from the BIP 173 examples
+    let public_key = secp256k1().get_generator().to_sec(true);
+    assert_eq!(
+        hash160(&public_key).to_vec(),
+        hex_to_bytes("751e76e8199196d454941c45d1b3a323f1433bd6")
+    );
+
+    // A MessageHash built from a message signs and verifies like one built from its digest
+    let z = MessageHash::hash256(b"hello");
+    assert_eq!(z.as_bytes(), &hash256(b"hello"));
+    assert_eq!(MessageHash::sha256(b"abc").as_bytes(), &sha256(b"abc"));
+    let key = PrivateKey::new(12345, secp256k1().get_curve());
+    let signature = key.sign(&z);
+    assert!(key
+        .get_public_key()
+        .get_point()
+        .verify_signature(&z, &signature));
+    assert!(!key
+        .get_public_key()
+        .get_point()
+        .verify_signature(&MessageHash::sha256(b"hello"), &signature));
+}
+
+#[test]
+fn test_hmac_sha256() {
+    // Test cases 1, 2 and 6 from RFC 4231
+    assert_eq!(
+        hmac_sha256(&[0x0b; 20], b"Hi There").to_vec(),
+        hex_to_bytes("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7")
+    );
+    assert_eq!(
+        hmac_sha256(b"Jefe", b"what do ya want for nothing?").to_vec(),
+        hex_to_bytes("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843")
+    );
+    assert_eq!(
+        hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )
+        .to_vec(),
+        hex_to_bytes("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54")
+    );
+}
+
+#[test]
+fn test_rfc6979() {
+    let n = BigUint::parse_bytes(
         b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
         16,
     )
     .unwrap();
+    let one = BigUint::from(1u8);
+    let cases: [(&BigUint, &[u8], &[u8]); 4] = [
+        (
+            &one,
+            b"Satoshi Nakamoto",
+            b"8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15",
+        ),
+        (
+            &one,
+            b"All those moments will be lost in time, like tears in rain. Time to die...",
+            b"38aa22d72376b4dbc472e06c3ba403ee0a394da63fc58d88686c611aba98d6b3",
+        ),
+        (
+            &(&n - 1u8),
+            b"Satoshi Nakamoto",
+            b"33a19b60e25fb6f4435af53a3d42d493644827367e6453928554f43e49aa6f90",
+        ),
+        (
+            &BigUint::parse_bytes(
+                b"f8b8af8ce3c7cca5e300d33939540c10d45ce001b8f252bfbc57ba0342904181",
+                16,
+            )
+            .unwrap(),
+            b"Alan Turing",
+            b"525a82b70e67874398067543fd84c83d30c175fdc45fdeee082fe13b1d7cfdf1",
+        ),
+    ];
+    for (secret, message, k) in cases.iter() {
+        assert_eq!(
+            generate_k(secret, &sha256(message), &n),
+            BigUint::parse_bytes(k, 16).unwrap()
+        );
+    }
+    // The detailed example from RFC 6979 appendix A.1, whose 163-bit order exercises the bit truncation
+    let q = BigUint::parse_bytes(b"4000000000000000000020108a2e0cc0d99f8a5ef", 16).unwrap();
+    let x = BigUint::parse_bytes(b"09a4d6792295a7f730fc3f2b49cbc0f62e862272f", 16).unwrap();
+    assert_eq!(
+        generate_k(&x, &sha256(b"sample"), &q),
+        BigUint::parse_bytes(b"23af4074c90a02b3fe61d286d5c87f425e6bdd81b", 16).unwrap()
+    );
+}
+
+#[test]
+fn test_sign_nonce_from_hash_bytes() {
+    // h1 is the hash exactly as hashed, even when the order is shorter than the hash and the hash
+    // starts with a zero byte
+    let curve = EllipticCurve::new(0, 5, 223).with_group(3, 60, 229, 1);
+    let n = curve.get_order().unwrap();
+    let key = PrivateKey::new(3, &curve);
+    let z = MessageHash::sha256(b"nonce 44");
+    assert_eq!(z.as_bytes()[0], 0);
+    let k = Scalar::new(generate_k(&BigUint::from(3u8), z.as_bytes(), n), n);
+    assert_eq!(Some(key.sign(&z)), key.sign_with_nonce(&z, &k));
+}
+
+#[test]
+fn test_sign_deterministic() {
+    let secp = secp256k1();
+    let key = PrivateKey::new(1, secp.get_curve());
+    let z = MessageHash::sha256(b"Satoshi Nakamoto");
+    let sig = key.sign(&z);
+    assert_eq!(
+        sig.get_r(),
+        &BigUint::parse_bytes(
+            b"934b1ea10a4b3c1757e2b0c017d0b6143ce3c9a7e6a4a49860d7a6ab210ee3d8",
+            16
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        sig.get_s(),
+        &BigUint::parse_bytes(
+            b"2442ce9d2b916064108014783e923ec36b49743e2ffa1c4496f01a512aafd9e5",
+            16
+        )
+        .unwrap()
+    );
+    assert_eq!(key.sign(&z), sig);
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_low_s() {
+    let secp = secp256k1();
+    let S256 = secp.get_curve();
+    let N = secp.get_order();
     let point = CurvePoint::new_large(
         b"887387e452b8eacc4acfde10d9aaf7f6d9a0f975aabb10d006e4da568744d06c",
         b"61de6d95231cd89026e286df3b6ae4a894a3378e393e93a0f45b666329a0ae34",
-        &S256,
+        S256,
     );
-    assert!(point.verify_signature(
-        b"ec208baa0fc1c19f708a9ca96fdeff3ac3f230bb4a7ba4aede4942ad003c0f60",
-        b"ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395",
-        b"68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4",
-        &G,
-        &N
-    ));
-    assert!(point.verify_signature(
-        b"7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d",
+    let z =
+        MessageHash::from_hex(b"7c076ff316692a3d7eb3c3bb0f8b1488cf72e1afcd929e29307032997a838a3d")
+            .unwrap();
+    let high = Signature::from_hex(
         b"eff69ef2b1bd93a66ed5219add4fb51e11a840f404876325a1e8ffe0529a2c",
         b"c7207fee197d27c618aea621406f6bf5ef6fca38681d82b2f06fddbdce6feab6",
-        &G,
-        &N
-    ));
+    )
+    .unwrap();
+
+    let mut low = high.clone();
+    assert!(!low.is_low_s(N));
+    low.normalize_s(N);
+    assert!(low.is_low_s(N));
+    assert_eq!(N - low.get_s(), *high.get_s());
+
+    // Both forms are valid ECDSA signatures, but strict verification only accepts the low one
+    assert!(point.verify_signature(&z, &low));
+    assert!(point.verify_signature_strict(&z, &low));
+    assert!(!point.verify_signature_strict(&z, &high));
+    assert!(!point.verify_signature_strict(&z, &Signature::new(high.get_r().clone(), 0)));
+}
+
+#[test]
+fn test_signature_constructors() {
+    let r = b"ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395";
+    let s = b"068342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4";
+    let sig = Signature::from_hex(r, s).unwrap();
+    assert_eq!(
+        sig,
+        Signature::new(
+            BigUint::parse_bytes(r, 16).unwrap(),
+            BigUint::parse_bytes(s, 16).unwrap()
+        )
+    );
+
+    let compact = sig.to_compact().unwrap();
+    assert_eq!(
+        compact.to_vec(),
+        hex_to_bytes(&format!(
+            "{}{}",
+            "ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395",
+            "068342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4"
+        ))
+    );
+    assert_eq!(Signature::from_compact(&compact), Ok(sig));
+
+    assert_eq!(Signature::from_hex(b"xyz", s), Err(Error::InvalidHex));
+    assert_eq!(Signature::from_hex(r, b""), Err(Error::InvalidHex));
+    assert_eq!(Signature::from_hex(b"+1", s), Err(Error::InvalidHex));
+    assert_eq!(
+        Signature::from_compact(&[0u8; 63]),
+        Err(Error::InvalidLength {
+            expected: 64,
+            found: 63
+        })
+    );
+    assert_eq!(
+        MessageHash::from_hex(&[b'f'; 66]),
+        Err(Error::InvalidLength {
+            expected: 32,
+            found: 33
+        })
+    );
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_verify_malformed_signature() {
+    let secp = secp256k1();
+    let S256 = secp.get_curve();
+    let G = secp.get_generator();
+    let N = secp.get_order();
+    let key = PublicKey::new(G.clone());
+    let z = MessageHash::from_hex(b"1").unwrap();
+    // (0, 0) would otherwise verify against any key, since both sides reduce to zero
+    assert_eq!(
+        Signature::new(0, 0).verify(&key, &z),
+        Err(Error::ScalarOutOfRange)
+    );
+    assert!(!G.verify_signature(&z, &Signature::new(0, 0)));
+    assert_eq!(
+        Signature::new(N.clone(), 1).verify(&key, &z),
+        Err(Error::ScalarOutOfRange)
+    );
+    let infinity = PublicKey::new(CurvePoint::infinity(S256));
+    assert_eq!(
+        Signature::new(1, 1).verify(&infinity, &z),
+        Err(Error::PointAtInfinity)
+    );
+    assert_eq!(Signature::try_new(-1, 1), Err(Error::NegativeValue));
+    assert_eq!(Signature::try_new(1, -1), Err(Error::NegativeValue));
+}
+
+#[test]
+fn test_der_roundtrip() {
+    let cases = [
+        (
+            "37206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6",
+            "8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec",
+            "3045022037206a0610995c58074999cb9767b87af4c4978db68c06e8e6e81d282047a7c6022100\
+             8ca63759c1157ebeaec0d03cecca119fc9a75bf8e6d0fa65c841c8e2738cdaec",
+        ),
+        (
+            "ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395",
+            "68342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4",
+            "3045022100ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395022006\
+             8342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4",
+        ),
+        ("1", "1", "3006020101020101"),
+        ("80", "7f", "30070202008002017f"),
+    ];
+    for (r, s, der) in cases.iter() {
+        let sig = Signature::from_hex(r.as_bytes(), s.as_bytes()).unwrap();
+        let der = hex_to_bytes(der);
+        assert_eq!(sig.to_der(), Ok(der.clone()));
+        assert_eq!(Signature::from_der(&der), Ok(sig));
+    }
+    // Integers past 32 bytes would need length bytes that BIP 66 doesn't allow
+    let big = BigUint::from(1u8) << 500;
+    assert_eq!(
+        Signature::new(big.clone(), 1).to_der(),
+        Err(Error::InvalidLength {
+            expected: 32,
+            found: 63
+        })
+    );
+    assert_eq!(
+        Signature::new(1, big).to_der(),
+        Err(Error::InvalidLength {
+            expected: 32,
+            found: 63
+        })
+    );
+    let max = (BigUint::from(1u8) << 256) - 1u8;
+    assert_eq!(Signature::new(max.clone(), max).to_der().unwrap().len(), 72);
+}
+
+#[test]
+fn test_der_strict_decoding() {
+    let invalid = [
+        // Too short and too long
+        ("30050201010201", DerError::BadTotalLength),
+        (
+            "3047022100ac8d1c87e51d0d441be8b3dd5b05c8795b48875dffe00b7ffcfac23010d3a395022200\
+             008342ceff8935ededd102dd876ffd6ba72d6a427a3edb13d26eb0781cb423c4ffff",
+            DerError::BadTotalLength,
+        ),
+        // Wrong structure tag
+        ("3106020101020101", DerError::MissingSequence),
+        // Total length byte doesn't match, r overruns the buffer, trailing garbage after s
+        ("3007020101020101", DerError::BadLength),
+        ("3006020501020101", DerError::BadLength),
+        ("300702010102010100", DerError::BadLength),
+        // Integers must be tagged as integers
+        ("3006030101020101", DerError::MissingInteger),
+        ("3006020101030101", DerError::MissingInteger),
+        // Zero-length r and s
+        ("3006020002020101", DerError::EmptyInteger),
+        ("3006020201010200", DerError::EmptyInteger),
+        // Negative r and s
+        ("3006020180020101", DerError::NegativeInteger),
+        ("3006020101020180", DerError::NegativeInteger),
+        // Unnecessary leading zeros in r and s
+        ("300702020001020101", DerError::ExcessPadding),
+        ("300702010102020001", DerError::ExcessPadding),
+    ];
+    for (der, reason) in invalid.iter() {
+        assert_eq!(
+            Signature::from_der(&hex_to_bytes(der)),
+            Err(Error::InvalidDer(*reason)),
+            "{}",
+            der
+        );
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_sec() {
+    let secp = secp256k1();
+    let S256 = secp.get_curve();
+    let G = secp.get_generator();
+    let cases: [(u64, bool, &str); 3] = [
+        (
+            5000,
+            false,
+            "04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1\
+             481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10",
+        ),
+        (
+            5001,
+            true,
+            "0357a4f368868a8a6d572991e484e664810ff14c05c0fa023275251151fe0e53d1",
+        ),
+        (
+            0xdeadbeef54321,
+            true,
+            "0296be5b1292f6c856b3c5654e886fc13511462059089cdf9c479623bfcbe77690",
+        ),
+    ];
+    for (secret, compressed, sec) in cases.iter() {
+        let point = &G * BigUint::from(*secret);
+        assert_eq!(point.to_sec(*compressed), hex_to_bytes(sec));
+        assert_eq!(CurvePoint::from_sec(&hex_to_bytes(sec), S256), Ok(point));
+    }
+}
+
+#[test]
+fn test_sec_toy_curve() {
+    let curve = EllipticCurve::new(0, 7, 223);
+    for (x, y) in [(192, 105), (47, 71), (47, 152), (143, 98)].iter() {
+        let point = CurvePoint::new(*x, *y, &curve);
+        for compressed in [false, true].iter() {
+            let sec = point.to_sec(*compressed);
+            assert_eq!(
+                CurvePoint::from_sec(&sec, &curve),
+                Ok(CurvePoint::from(&point))
+            );
+        }
+    }
+    let infinity = CurvePoint::infinity(&curve);
+    assert_eq!(infinity.to_sec(true), vec![0x00]);
+    assert_eq!(CurvePoint::from_sec(&[0x00], &curve), Ok(infinity));
+
+    assert_eq!(
+        CurvePoint::from_sec(&[0x05, 47], &curve),
+        Err(Error::InvalidSecPrefix(0x05))
+    );
+    assert_eq!(
+        CurvePoint::from_sec(&[0x02, 47, 71], &curve),
+        Err(Error::InvalidLength {
+            expected: 2,
+            found: 3
+        })
+    );
+    // 4**3 + 7 is not a square mod 223, so no point has x = 4
+    assert_eq!(
+        CurvePoint::from_sec(&[0x02, 4], &curve),
+        Err(Error::PointNotOnCurve)
+    );
+    assert_eq!(
+        CurvePoint::from_sec(&[0x04, 200, 119], &curve),
+        Err(Error::PointNotOnCurve)
+    );
+    // Coordinates must be reduced mod p
+    assert_eq!(
+        CurvePoint::from_sec(&[0x04, 47, 0xff], &curve),
+        Err(Error::PointNotOnCurve)
+    );
+    // x**3 + 7 = 0 for these x, so y = 0 is even and 0x03 would be a second encoding
+    for x in [6u8, 11, 206].iter() {
+        let point = CurvePoint::new(*x, 0, &curve);
+        assert_eq!(point.to_sec(true), vec![0x02, *x]);
+        assert_eq!(CurvePoint::from_sec(&[0x02, *x], &curve), Ok(point));
+        assert_eq!(
+            CurvePoint::from_sec(&[0x03, *x], &curve),
+            Err(Error::InvalidSecPrefix(0x03))
+        );
+    }
+}
+
+#[test]
+fn test_sqrt_fieldelement() {
+    // 223 = 3 (mod 4) takes the fast path, the rest need Tonelli-Shanks. 257 - 1 = 2**8 exercises
+    // the deepest possible search for its size
+    for prime in [223u32, 17, 97, 257, 401].iter() {
+        let mut squares = 0;
+        for value in 0..*prime {
+            let a = FieldElement::new(value, *prime);
+            match a.sqrt() {
+                Some(root) => {
+                    assert!(a.is_square());
+                    assert_eq!(root.pow(2), a);
+                    squares += 1;
+                }
+                None => assert!(!a.is_square()),
+            }
+        }
+        // Zero and the (p - 1) / 2 nonzero quadratic residues
+        assert_eq!(squares, (prime - 1) / 2 + 1);
+    }
+}
+
+#[test]
+fn test_sqrt_composite_modulus() {
+    // Tonelli-Shanks assumes a prime modulus. Over composites it must still return, and any root
+    // it gives must be a true root. 561 is a Carmichael number.
+    for modulus in [21u32, 65, 16, 45, 561].iter() {
+        for value in 0..*modulus {
+            let a = FieldElement::new(value, *modulus);
+            if let Some(root) = a.sqrt() {
+                assert_eq!(&root * &root, a);
+            }
+        }
+    }
+    assert_eq!(FieldElement::new(8, 21).sqrt(), None);
+    let curve = EllipticCurve::try_new(0, 7, 65).unwrap();
+    assert!(CurvePoint::from_sec(&[0x02, 0x01], &curve).is_err());
+}
+
+#[test]
+fn test_sqrt_fieldelement_large() {
+    // The secp256k1 prime: y is a square root of x**3 + 7 for the generator
+    let p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    let x = FieldElement::new(
+        BigUint::parse_bytes(
+            b"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            16,
+        )
+        .unwrap(),
+        p.clone(),
+    );
+    let y = FieldElement::new(
+        BigUint::parse_bytes(
+            b"483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            16,
+        )
+        .unwrap(),
+        p.clone(),
+    );
+    let root = (x.pow(3) + 7).sqrt().unwrap();
+    assert!(root == y || root == FieldElement::new(&p - y.get_value(), p.clone()));
+    assert!(!FieldElement::new(5, p.clone()).is_square());
+
+    // The secp256k1 group order is 1 (mod 4), so it needs Tonelli-Shanks
+    let n = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16,
+    )
+    .unwrap();
+    let b = FieldElement::new(
+        BigUint::parse_bytes(b"deadbeefcafebabe1234567890", 16).unwrap(),
+        n.clone(),
+    );
+    let root = b.pow(2).sqrt().unwrap();
+    assert!(root == b || root == FieldElement::new(&n - b.get_value(), n.clone()));
+}
+
+#[test]
+fn test_fallible_constructors() {
+    assert_eq!(FieldElement::try_new(-1, 31), Err(Error::NegativeValue));
+    assert_eq!(FieldElement::try_new(5, 1), Err(Error::InvalidModulus));
+    assert_eq!(FieldElement::try_new(33, 31), Ok(FieldElement::new(2, 31)));
+    assert_eq!(
+        FieldElement::try_from_hex(b"1g", b"1f"),
+        Err(Error::InvalidHex)
+    );
+    assert_eq!(
+        FieldElement::try_from_hex(b"1a", b"1f"),
+        Ok(FieldElement::new(26, 31))
+    );
+
+    let a = FieldElement::new(3, 31);
+    let zero = FieldElement::new(0, 31);
+    let other = FieldElement::new(3, 37);
+    assert_eq!(a.try_add(&other), Err(Error::MismatchedPrimes));
+    assert_eq!(a.try_sub(&other), Err(Error::MismatchedPrimes));
+    assert_eq!(a.try_mul(&other), Err(Error::MismatchedPrimes));
+    assert_eq!(a.try_div(&zero), Err(Error::DivisionByZero));
+    assert_eq!(
+        a.try_div(&FieldElement::new(24, 31)),
+        Ok(FieldElement::new(4, 31))
+    );
+
+    assert_eq!(EllipticCurve::try_new(0, 0, 223), Err(Error::SingularCurve));
+    // 4 * (-3)**3 + 27 * 2**2 = 0
+    assert_eq!(
+        EllipticCurve::try_new(220, 2, 223),
+        Err(Error::SingularCurve)
+    );
+    assert_eq!(EllipticCurve::try_new(0, 7, 1), Err(Error::InvalidModulus));
+    assert_eq!(
+        EllipticCurve::try_from_hex(b"0", b"7", b"xyz"),
+        Err(Error::InvalidHex)
+    );
+
+    let curve = EllipticCurve::try_new(0, 7, 223).unwrap();
+    assert_eq!(
+        CurvePoint::try_new(200, 119, &curve).unwrap_err(),
+        Error::PointNotOnCurve
+    );
+    assert_eq!(
+        CurvePoint::try_from_hex(b"c0", b"69", &curve),
+        Ok(CurvePoint::new(192, 105, &curve))
+    );
+    assert_eq!(
+        EllipticCurve::new(0, 7, 223)
+            .try_with_group(15, 86, 5, 1)
+            .unwrap_err(),
+        Error::InvalidGroupOrder
+    );
+    assert_eq!(
+        EllipticCurve::new(0, 7, 223)
+            .try_with_group(200, 119, 7, 36)
+            .unwrap_err(),
+        Error::PointNotOnCurve
+    );
+
+    let other_curve = EllipticCurve::new(0, 5, 223);
+    let p1 = CurvePoint::new(192, 105, &curve);
+    let p2 = CurvePoint::infinity(&other_curve);
+    assert_eq!(p1.try_add(&p2).unwrap_err(), Error::MismatchedCurves);
+    assert_eq!(p1.try_add(&p1), Ok(CurvePoint::new(49, 71, &curve)));
+
+    let group = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    assert_eq!(
+        PrivateKey::try_new(3, &curve).unwrap_err(),
+        Error::MissingGroup
+    );
+    assert_eq!(
+        PrivateKey::try_new(0, &group).unwrap_err(),
+        Error::ScalarOutOfRange
+    );
+    assert_eq!(
+        PrivateKey::try_new(7, &group).unwrap_err(),
+        Error::ScalarOutOfRange
+    );
+    assert!(PrivateKey::try_new(6, &group).is_ok());
+}
+
+#[test]
+fn test_curve_mul_matches_repeated_addition() {
+    // Every multiple of these points, including ones that hit a point with y = 0 or wrap past
+    // infinity, must agree with adding the point to itself one step at a time
+    let curve = EllipticCurve::new(0, 7, 223);
+    for point in &[
+        CurvePoint::new(192, 105, &curve),
+        CurvePoint::new(47, 71, &curve),
+        CurvePoint::new(15, 86, &curve),
+    ] {
+        let mut sum = CurvePoint::infinity(&curve);
+        for k in 0..100 {
+            assert_eq!(point * k, sum);
+            sum = &sum + point;
+        }
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_ladder_matches_vartime() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let n = curve.get_order().unwrap();
+    for point in &[
+        CurvePoint::new(15, 86, &curve),
+        CurvePoint::new(139, 86, &curve),
+        CurvePoint::infinity(&curve),
+    ] {
+        // Past 2 * 252 to cover integers that need reducing
+        for k in 0..520 {
+            let scalar = Scalar::new(k, n);
+            assert_eq!(point * k, point.mul_vartime(&scalar));
+            assert_eq!(point * &scalar, point * k);
+        }
+    }
+
+    let secp = secp256k1();
+    let G = secp.get_generator();
+    let k = Scalar::from_bytes_reduced(
+        &hex_to_bytes("deadbeefcafebabe1234567890deadbeefcafebabe1234567890deadbeef"),
+        secp.get_order(),
+    );
+    assert_eq!(&G * &k, G.mul_vartime(&k));
+    assert!((&G * secp.get_order()).is_infinity());
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_mul_generator() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let G = curve.get_generator().unwrap();
+    let n = curve.get_order().unwrap();
+    for k in 0..20 {
+        assert_eq!(curve.mul_generator(&Scalar::new(k, n)).unwrap(), &G * k);
+    }
+    let three = Scalar::new(3, n);
+    assert_eq!(EllipticCurve::new(0, 7, 223).mul_generator(&three), None);
+
+    let secp = secp256k1();
+    let G = secp.get_generator();
+    let n = secp.get_order();
+    assert!(secp.mul_generator(&Scalar::new(0, n)).is_infinity());
+    assert_eq!(secp.mul_generator(&Scalar::new(1, n)), G);
+    assert!(secp.mul_generator(&Scalar::new(n.clone(), n)).is_infinity());
+    let n_less_one = -Scalar::new(1, n);
+    assert_eq!(secp.mul_generator(&n_less_one), &G * &n_less_one);
+    let k = Scalar::from_bytes_reduced(
+        &hex_to_bytes("deadbeefcafebabe1234567890deadbeefcafebabe1234567890deadbeef"),
+        n,
+    );
+    assert_eq!(secp.mul_generator(&k), &G * &k);
+}
+
+#[test]
+#[should_panic(expected = "Scalars have different group orders")]
+fn test_mul_generator_wrong_order() {
+    let secp = secp256k1();
+    secp.mul_generator(&Scalar::new(3, &BigUint::from(7u8)));
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let n = curve.get_order().unwrap();
+    let p1 = CurvePoint::new(15, 86, &curve);
+    let p2 = CurvePoint::new(139, 86, &curve);
+    let p3 = &p1 * 3;
+    assert!(curve.multi_scalar_mul(&[]).is_infinity());
+    for (k1, k2, k3) in &[(0u32, 0u32, 0u32), (1, 2, 3), (41, 20, 6), (251, 300, 1000)] {
+        let expected = &p1 * *k1 + &p2 * *k2 + &p3 * *k3;
+        let (k1, k2, k3) = (
+            Scalar::new(*k1, n),
+            Scalar::new(*k2, n),
+            Scalar::new(*k3, n),
+        );
+        let terms = [(&k1, &p1), (&k2, &p2), (&k3, &p3)];
+        assert_eq!(curve.multi_scalar_mul(&terms), expected);
+    }
+
+    let secp = secp256k1();
+    let n = secp.get_order();
+    let g = secp.get_generator();
+    let p = secp.mul_generator(&Scalar::new(12345, n));
+    let u = -Scalar::new(2, n);
+    let v = Scalar::from_bytes_reduced(&hex_to_bytes("deadbeefcafebabe1234567890"), n);
+    assert_eq!(
+        secp.get_curve().multi_scalar_mul(&[(&u, &g), (&v, &p)]),
+        g.mul_vartime(&u) + p.mul_vartime(&v)
+    );
+}
+
+#[test]
+#[should_panic]
+fn test_multi_scalar_mul_wrong_curve() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let other_curve = EllipticCurve::new(0, 5, 223).with_group(3, 60, 229, 1);
+    let p = CurvePoint::new(192, 105, &curve);
+    other_curve.multi_scalar_mul(&[(&Scalar::new(2, &BigUint::from(229u8)), &p)]);
+}
+
+#[test]
+#[should_panic(expected = "is not on the curve")]
+fn test_multi_scalar_mul_other_group() {
+    // Same equation and order, but the generator 2G makes it a different group
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let double = curve.get_generator().unwrap() * 2;
+    let other_curve = EllipticCurve::new(0, 7, 223).with_group(
+        double.get_x().get_value().clone(),
+        double.get_y().get_value().clone(),
+        7,
+        36,
+    );
+    let p = curve.get_generator().unwrap();
+    other_curve.multi_scalar_mul(&[(&Scalar::new(2, &BigUint::from(7u8)), &p)]);
+}
+
+#[test]
+#[should_panic(expected = "Scalars have different group orders")]
+fn test_multi_scalar_mul_wrong_order() {
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    let p = curve.get_generator().unwrap();
+    curve.multi_scalar_mul(&[(&Scalar::new(2, &BigUint::from(11u8)), &p)]);
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_endomorphism() {
+    let secp = secp256k1();
+    let G = secp.get_generator();
+    let n = secp.get_order();
+    let P = secp.mul_generator(&Scalar::new(0xdeadbeefu32, n));
+    for i in 0u32..8 {
+        let k = Scalar::from_bytes_reduced(&sha256(&i.to_be_bytes()), n);
+        assert_eq!(G.mul_vartime(&k), &G * &k);
+        assert_eq!(P.mul_vartime(&k), &P * &k);
+        let v = Scalar::from_bytes_reduced(&sha256(&k.to_bytes()), n);
+        assert_eq!(
+            secp.get_curve().multi_scalar_mul(&[(&k, &G), (&v, &P)]),
+            &G * &k + &P * &v
+        );
+    }
+    assert!(G.mul_vartime(&Scalar::new(n.clone(), n)).is_infinity());
+    assert_eq!(G.mul_vartime(&Scalar::new(n + 1u8, n)), G);
+
+    let lambda = BigUint::parse_bytes(
+        b"5363ad4cc05c30e0a5261c028812645a122e22ea20816678df02967c1b23bd72",
+        16,
+    )
+    .unwrap();
+    let beta = BigUint::parse_bytes(
+        b"7ae96a2b657c07106e64479eac3434e99cf0497512f58995c1396c28719501ee",
+        16,
+    )
+    .unwrap();
+    let curve = secp.get_curve().clone();
+    // lambda**2 is the other nontrivial cube root, which pairs with beta**2 instead of beta
+    let lambda_squared = &lambda * &lambda % secp.get_order();
+    assert_eq!(
+        curve
+            .clone()
+            .try_with_endomorphism(beta.clone(), lambda_squared)
+            .unwrap_err(),
+        Error::InvalidEndomorphism
+    );
+    assert_eq!(
+        curve.try_with_endomorphism(1, lambda.clone()).unwrap_err(),
+        Error::InvalidEndomorphism
+    );
+    assert_eq!(
+        EllipticCurve::new(0, 7, 223)
+            .try_with_endomorphism(beta, lambda)
+            .unwrap_err(),
+        Error::MissingGroup
+    );
+}
+
+#[cfg(test)]
+fn curve_rhs<F: Field>(x: &F) -> F {
+    // x**3 + 7, written once for every field backend
+    let seven = (0..7).fold(x.zero(), |acc, _| acc + x.one());
+    x.squared() * x.clone() + seven
+}
+
+#[test]
+fn test_fe256() {
+    let p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    let p_less_one = &p - 1u8;
+    let mut values = vec![
+        BigUint::from(0u8),
+        BigUint::from(1u8),
+        BigUint::from(7u8),
+        p_less_one.clone(),
+        &p - 2u8,
+        BigUint::from(u64::MAX),
+    ];
+    for i in 0u32..16 {
+        values.push(BigUint::from_bytes_be(&sha256(&i.to_be_bytes())) % &p);
+    }
+    for a in &values {
+        let fa = Fe256::from_biguint(a);
+        let ea = FieldElement::new(a.clone(), p.clone());
+        assert_eq!(&fa.to_biguint(), a);
+        assert_eq!(Fe256::from_bytes(&fa.to_bytes()), Some(fa));
+        assert_eq!((-fa).to_biguint(), (-ea.clone()).get_value().clone());
+        match a == &BigUint::from(0u8) {
+            true => assert_eq!(fa.invert(), None),
+            false => assert_eq!(
+                fa.invert().unwrap().to_biguint(),
+                (FieldElement::new(1, p.clone()) / &ea).get_value().clone()
+            ),
+        }
+        assert_eq!(
+            curve_rhs(&fa).to_biguint(),
+            curve_rhs(&ea).get_value().clone()
+        );
+        for b in &values {
+            let fb = Fe256::from_biguint(b);
+            let eb = FieldElement::new(b.clone(), p.clone());
+            assert_eq!((fa + fb).to_biguint(), (&ea + &eb).get_value().clone());
+            assert_eq!((fa - fb).to_biguint(), (&ea - &eb).get_value().clone());
+            assert_eq!((fa * fb).to_biguint(), (&ea * &eb).get_value().clone());
+        }
+        let square = fa.square();
+        let root = square.sqrt().unwrap();
+        assert!(root == fa || root == -fa);
+    }
+    // -1 is not a square since p = 3 (mod 4)
+    assert_eq!(Fe256::from_biguint(&p_less_one).sqrt(), None);
+    assert_eq!(Fe256::from_biguint(&p), Fe256::zero());
+    let mut p_bytes = [0u8; 32];
+    p_bytes.copy_from_slice(&p.to_bytes_be());
+    assert_eq!(Fe256::from_bytes(&p_bytes), None);
+}
+
+#[test]
+fn test_curve_over_fe256() {
+    let reference = secp256k1().get_curve();
+    let generator = reference.get_generator().unwrap();
+    let curve = EllipticCurve::from_coefficients(Fe256::zero(), Fe256::from_u64(7)).with_group(
+        generator.get_x().get_value().clone(),
+        generator.get_y().get_value().clone(),
+        reference.get_order().unwrap().clone(),
+        1,
+    );
+    let g = curve.get_generator().unwrap();
+    let n = reference.get_order().unwrap();
+    assert!((&g * n).is_infinity());
+
+    let k = Scalar::from_bytes_reduced(&sha256(b"fe256 curve"), n);
+    let expected = &generator * &k;
+    for point in [curve.mul_generator(&k).unwrap(), &g * &k, g.mul_vartime(&k)] {
+        assert_eq!(&point.get_x().to_biguint(), expected.get_x().get_value());
+        assert_eq!(&point.get_y().to_biguint(), expected.get_y().get_value());
+    }
+    assert_eq!(g.to_sec(true), generator.to_sec(true));
+    assert_eq!(
+        CurvePoint::from_sec(&expected.to_sec(true), &curve).unwrap(),
+        &g * &k
+    );
+
+    let z = MessageHash::new(sha256(b"generic keys"));
+    let key = PrivateKey::new(12345, &curve);
+    let signature = key.sign(&z);
+    assert_eq!(signature, PrivateKey::new(12345, reference).sign(&z));
+    assert!(signature.verify(key.get_public_key(), &z).unwrap());
+}
+
+#[test]
+fn test_batch_invert() {
+    let p = BigUint::from(1_000_003u32);
+    let values = [5u32, 0, 1, 999_999, 0, 123_456, 2];
+    let mut elements: Vec<FieldElement> = values
+        .iter()
+        .map(|&v| FieldElement::new(v, p.clone()))
+        .collect();
+    FieldElement::batch_invert(&mut elements);
+    for (&v, inverse) in values.iter().zip(&elements) {
+        let element = FieldElement::new(v, p.clone());
+        match v {
+            0 => assert_eq!(inverse, &element),
+            _ => assert_eq!(inverse, &(FieldElement::new(1, p.clone()) / &element)),
+        }
+    }
+    FieldElement::batch_invert(&mut []);
+
+    let mut fe256: Vec<Fe256> = (1..10).map(Fe256::from_u64).collect();
+    Fe256::batch_invert(&mut fe256);
+    for (i, inverse) in (1..10).zip(fe256) {
+        assert_eq!(inverse * Fe256::from_u64(i), Fe256::one());
+    }
+}
+
+#[test]
+#[should_panic(expected = "Primes don't match!")]
+fn test_batch_invert_mismatched_primes() {
+    let mut elements = [FieldElement::new(3, 31), FieldElement::new(3, 37)];
+    FieldElement::batch_invert(&mut elements);
+}
+
+#[test]
+fn test_invert() {
+    let p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    for i in 0u32..16 {
+        let x = FieldElement::new(BigUint::from_bytes_be(&sha256(&i.to_be_bytes())), p.clone());
+        let fermat = x.pow(&p - 2u8);
+        assert_eq!(x.invert(), Some(fermat.clone()));
+        assert_eq!(x.invert_constant_time(), Some(fermat));
+    }
+    for prime in [2u32, 3, 31, 65537] {
+        assert_eq!(FieldElement::new(0, prime).invert(), None);
+        assert_eq!(FieldElement::new(0, prime).invert_constant_time(), None);
+        for value in 1..std::cmp::min(prime, 100) {
+            let x = FieldElement::new(value, prime);
+            let inverse = x.invert().unwrap();
+            assert_eq!(&x * &inverse, FieldElement::new(1, prime));
+            assert_eq!(x.invert_constant_time(), Some(inverse));
+        }
+    }
+    // Without a prime modulus only values coprime to it are invertible
+    assert_eq!(FieldElement::new(6, 15).invert(), None);
+    assert_eq!(FieldElement::new(6, 15).invert_constant_time(), None);
+    assert_eq!(
+        FieldElement::new(7, 15).invert(),
+        Some(FieldElement::new(13, 15))
+    );
+    assert_eq!(
+        FieldElement::new(7, 15).invert_constant_time(),
+        Some(FieldElement::new(13, 15))
+    );
+}
+
+#[test]
+#[should_panic(expected = "Division by zero")]
+fn test_divide_by_zero() {
+    let _ = FieldElement::new(5, 31) / FieldElement::new(0, 31);
+}
+
+#[cfg(test)]
+fn is_shareable<T: Send + Sync + 'static>(_: &T) {}
+
+#[cfg(test)]
+fn local_point() -> CurvePoint {
+    // The curve goes out of scope here, but the point keeps its own handle to it
+    let curve = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    curve.get_generator().unwrap() * 2
+}
+
+#[test]
+fn test_owned_points() {
+    let point = local_point();
+    assert_eq!(point.get_x().get_value(), &BigUint::from(139u8));
+    assert_eq!(point.get_y().get_value(), &BigUint::from(86u8));
+    assert!((&point * 7).is_infinity());
+
+    let key = PrivateKey::new(12345, secp256k1().get_curve());
+    is_shareable(&point);
+    is_shareable(&key);
+    let z = MessageHash::new(sha256(b"owned points"));
+    let public_key = key.get_public_key().clone();
+    let signature = std::thread::spawn(move || key.sign(&z)).join().unwrap();
+    let verified = std::thread::spawn(move || signature.verify(&public_key, &z))
+        .join()
+        .unwrap();
+    assert!(verified.unwrap());
+}
+
+#[test]
+fn test_scalar() {
+    let n = secp256k1().get_order().clone();
+    let a = Scalar::from_bytes_reduced(&sha256(b"a"), &n);
+    let b = Scalar::from_bytes_reduced(&sha256(b"b"), &n);
+    let one = Scalar::new(1, &n);
+    assert_eq!(&(&a + &b) - &b, a);
+    assert_eq!(&a + &(-&a), Scalar::new(0, &n));
+    assert_eq!(&a * &a.invert().unwrap(), one);
+    assert_eq!(a.invert_constant_time(), a.invert());
+    assert_eq!(Scalar::new(0, &n).invert(), None);
+    assert_eq!(-Scalar::new(0, &n), Scalar::new(0, &n));
+    assert_eq!((&a * &b).get_value(), &(a.get_value() * b.get_value() % &n));
+    // Reducing n + 1 gives 1, and bytes round trip at the length of n
+    assert_eq!(
+        Scalar::from_bytes_reduced(&(&n + 1u8).to_bytes_be(), &n),
+        one
+    );
+    assert_eq!(one.to_bytes().len(), 32);
+    assert_eq!(Scalar::from_bytes_reduced(&a.to_bytes(), &n), a);
+    assert!(!a.ct_eq(&b));
+    assert!(!one.ct_eq(&Scalar::new(1, &BigUint::from(7u8))));
+    assert_eq!(
+        Scalar::try_new(1, &BigUint::from(1u8)),
+        Err(Error::InvalidModulus)
+    );
+
+    // Scalars multiply points like integers do
+    let g = secp256k1().get_generator();
+    assert_eq!(&g * &a, &g * a.get_value().clone());
+    let key = PrivateKey::new(a.get_value().clone(), secp256k1().get_curve());
+    assert_eq!(key.get_secret(), &a);
+}
+
+#[test]
+#[should_panic(expected = "Scalars have different group orders")]
+fn test_scalar_mismatched_orders() {
+    let _ = Scalar::new(1, &BigUint::from(7u8)) + Scalar::new(1, &BigUint::from(11u8));
+}
+
+#[test]
+fn test_encodings() {
+    // RFC 4648 test vectors
+    let vectors = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+    for (data, encoded) in vectors.iter() {
+        assert_eq!(&base64_encode(data.as_bytes()), encoded);
+        assert_eq!(base64_decode(encoded), Ok(data.as_bytes().to_vec()));
+    }
+    for invalid in ["Zg=", "Zh==", "Z===", "Zm9v!A==", "=Zm9"].iter() {
+        assert_eq!(base64_decode(invalid), Err(Error::InvalidBase64));
+    }
+
+    // Valid and invalid strings from BIP 173
+    assert_eq!(bech32_decode("A12UEL5L"), Ok(("a".to_string(), vec![])));
+    assert_eq!(bech32_encode("a", &[]), "a12uel5l");
+    let long = "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw";
+    let (hrp, data) = bech32_decode(long).unwrap();
+    assert_eq!(bech32_encode(&hrp, &data), long);
+    assert_eq!(bech32_decode("A1G7SGD8"), Err(Error::InvalidChecksum));
+    assert_eq!(bech32_decode("a12UEL5L"), Err(Error::InvalidBech32));
+    assert_eq!(bech32_decode("pzry9x0s0muk"), Err(Error::InvalidBech32));
+    assert_eq!(bech32_decode("10a06t8"), Err(Error::InvalidBech32));
+
+    assert_eq!(
+        base58check_decode("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMI"),
+        Err(Error::InvalidBase58)
+    );
+    assert_eq!(
+        base58check_decode("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMJ"),
+        Err(Error::InvalidChecksum)
+    );
+}
+
+#[test]
+fn test_addresses() {
+    // The public key for secret 1 is G
+    let key = PrivateKey::new(1, secp256k1().get_curve());
+    let key = key.get_public_key();
+    let vectors = [
+        (
+            Address::p2pkh(key, false),
+            "1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm",
+        ),
+        (
+            Address::p2pkh(key, true),
+            "1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH",
+        ),
+        (
+            Address::p2wpkh(key),
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        ),
+    ];
+    for (address, encoded) in vectors.iter() {
+        assert_eq!(&address.to_string(), encoded);
+        assert_eq!(&encoded.parse::<Address>().unwrap(), address);
+    }
+    assert_eq!(
+        "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4".parse::<Address>(),
+        Ok(Address::p2wpkh(key))
+    );
+    // P2SH, P2WSH, taproot and testnet addresses aren't supported
+    let unsupported = [
+        "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+        "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+        "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0",
+        "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn",
+    ];
+    for address in unsupported.iter() {
+        assert!(address.parse::<Address>().is_err());
+    }
+}
+
+#[test]
+fn test_signed_messages() {
+    // From the bitcoinjs-message examples: the key is the WIF L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1
+    let wif = base58check_decode("L4rK1yDtCWekvXuE6oXD9jCYfFNV2cWRpVuPLBcCU2z8TrisoyY1").unwrap();
+    let key = PrivateKey::new(BigUint::from_bytes_be(&wif[1..33]), secp256k1().get_curve());
+    let message = b"This is an example of a signed message.";
+    let signature =
+        "H9L5yLFjti0QTHhPyFrZCT1V/MMnBtXKmoiKDZ78NDBjERki6ZTQZdSMCtkgoNmp17By9ItJr8o7ChX0XxY91nk=";
+    assert_eq!(
+        sign_message(&key, message, AddressType::P2pkh),
+        Ok(signature.to_string())
+    );
+    assert_eq!(
+        verify_message("1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV", signature, message),
+        Ok(true)
+    );
+    assert_eq!(
+        verify_message(
+            "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            signature,
+            b"Another message"
+        ),
+        Ok(false)
+    );
+
+    for i in 1u32..4 {
+        let key = PrivateKey::new(
+            BigUint::from_bytes_be(&sha256(&i.to_be_bytes())),
+            secp256k1().get_curve(),
+        );
+        let public_key = key.get_public_key();
+        let cases = [
+            (
+                AddressType::P2pkhUncompressed,
+                Address::p2pkh(public_key, false),
+            ),
+            (AddressType::P2pkh, Address::p2pkh(public_key, true)),
+            (AddressType::P2wpkh, Address::p2wpkh(public_key)),
+        ];
+        for (address_type, address) in cases.iter() {
+            let signature = sign_message(&key, b"proof of ownership", *address_type).unwrap();
+            let address = address.to_string();
+            assert_eq!(
+                verify_message(&address, &signature, b"proof of ownership"),
+                Ok(true)
+            );
+            assert_eq!(
+                verify_message(&address, &signature, b"proof of ownership!"),
+                Ok(false)
+            );
+        }
+        // The uncompressed key's address differs from the compressed one's
+        let signature = sign_message(&key, b"proof", AddressType::P2pkhUncompressed).unwrap();
+        let address = Address::p2pkh(public_key, true).to_string();
+        assert_eq!(verify_message(&address, &signature, b"proof"), Ok(false));
+    }
+
+    // The header's address type must match the address: only the P2SH-P2WPKH headers 35 to 38
+    // and the uncompressed P2PKH headers are wrong for P2WPKH, and only 27 to 34 fit P2PKH
+    let key = PrivateKey::new(12345, secp256k1().get_curve());
+    let p2pkh = Address::p2pkh(key.get_public_key(), true).to_string();
+    let p2wpkh = Address::p2wpkh(key.get_public_key()).to_string();
+    let segwit = base64_decode(&sign_message(&key, message, AddressType::P2wpkh).unwrap()).unwrap();
+    let recid = segwit[0] - 39;
+    let with_header = |base: u8| {
+        let mut bytes = segwit.clone();
+        bytes[0] = base + recid;
+        base64_encode(&bytes)
+    };
+    assert_eq!(verify_message(&p2wpkh, &with_header(39), message), Ok(true));
+    assert_eq!(verify_message(&p2wpkh, &with_header(31), message), Ok(true));
+    assert_eq!(
+        verify_message(&p2wpkh, &with_header(35), message),
+        Ok(false)
+    );
+    assert_eq!(
+        verify_message(&p2wpkh, &with_header(27), message),
+        Ok(false)
+    );
+    assert_eq!(verify_message(&p2pkh, &with_header(31), message), Ok(true));
+    assert_eq!(verify_message(&p2pkh, &with_header(35), message), Ok(false));
+    assert_eq!(verify_message(&p2pkh, &with_header(39), message), Ok(false));
+
+    // Keys from other curves, or from secp256k1 with another generator, can't sign messages
+    let toy = EllipticCurve::new(0, 7, 223).with_group(15, 86, 7, 36);
+    assert_eq!(
+        sign_message(&PrivateKey::new(3, &toy), message, AddressType::P2pkh),
+        Err(Error::MismatchedCurves)
+    );
+    let secp = secp256k1();
+    let double = secp.get_generator() * 2;
+    let other_generator = secp.get_curve().clone().with_group(
+        double.get_x().get_value().clone(),
+        double.get_y().get_value().clone(),
+        secp.get_order().clone(),
+        1,
+    );
+    assert_eq!(
+        sign_message(
+            &PrivateKey::new(3, &other_generator),
+            message,
+            AddressType::P2pkh
+        ),
+        Err(Error::MismatchedCurves)
+    );
+
+    let mut bytes = base64_decode(signature).unwrap();
+    bytes[0] = 43;
+    assert_eq!(
+        verify_message(
+            "1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV",
+            &base64_encode(&bytes),
+            message
+        ),
+        Err(Error::InvalidSignatureHeader(43))
+    );
+    assert_eq!(
+        verify_message("1F3sAm6ZtwLAUnj7d38pGFxtP3RVEvtsbV", "Zm9v", message),
+        Err(Error::InvalidLength {
+            expected: 65,
+            found: 3
+        })
+    );
+}
+
+// The test vectors from BIP 340 (bip-0340/test-vectors.csv)
+#[cfg(test)]
+const BIP340_VECTORS: &str = "index,secret key,public key,aux_rand,message,signature,verification result,comment
+0,0000000000000000000000000000000000000000000000000000000000000003,F9308A019258C31049344F85F89D5229B531C845836F99B08601F113BCE036F9,0000000000000000000000000000000000000000000000000000000000000000,0000000000000000000000000000000000000000000000000000000000000000,E907831F80848D1069A5371B402410364BDF1C5F8307B0084C55F1CE2DCA821525F66A4A85EA8B71E482A74F382D2CE5EBEEE8FDB2172F477DF4900D310536C0,TRUE,
+1,B7E151628AED2A6ABF7158809CF4F3C762E7160F38B4DA56A784D9045190CFEF,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,0000000000000000000000000000000000000000000000000000000000000001,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,6896BD60EEAE296DB48A229FF71DFE071BDE413E6D43F917DC8DCF8C78DE33418906D11AC976ABCCB20B091292BFF4EA897EFCB639EA871CFA95F6DE339E4B0A,TRUE,
+2,C90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B14E5C9,DD308AFEC5777E13121FA72B9CC1B7CC0139715309B086C960E18FD969774EB8,C87AA53824B4D7AE2EB035A2B5BBBCCC080E76CDC6D1692C4B0B62D798E6D906,7E2D58D8B3BCDF1ABADEC7829054F90DDA9805AAB56C77333024B9D0A508B75C,5831AAEED7B44BB74E5EAB94BA9D4294C49BCF2A60728D8B4C200F50DD313C1BAB745879A5AD954A72C45A91C3A51D3C7ADEA98D82F8481E0E1E03674A6F3FB7,TRUE,
+3,0B432B2677937381AEF05BB02A66ECD012773062CF3FA2549E44F58ED2401710,25D1DFF95105F5253C4022F628A996AD3A0D95FBF21D468A1B33F8C160D8F517,FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF,FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF,7EB0509757E246F19449885651611CB965ECC1A187DD51B64FDA1EDC9637D5EC97582B9CB13DB3933705B32BA982AF5AF25FD78881EBB32771FC5922EFC66EA3,TRUE,test fails if msg is reduced modulo p or n
+4,,D69C3509BB99E412E68B0FE8544E72837DFA30746D8BE2AA65975F29D22DC7B9,,4DF3C3F68FCC83B27E9D42C90431A72499F17875C81A599B566C9889B9696703,00000000000000000000003B78CE563F89A0ED9414F5AA28AD0D96D6795F9C6376AFB1548AF603B3EB45C9F8207DEE1060CB71C04E80F593060B07D28308D7F4,TRUE,
+5,,EEFDEA4CDB677750A420FEE807EACF21EB9898AE79B9768766E4FAA04A2D4A34,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B,FALSE,public key not on the curve
+6,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,FFF97BD5755EEEA420453A14355235D382F6472F8568A18B2F057A14602975563CC27944640AC607CD107AE10923D9EF7A73C643E166BE5EBEAFA34B1AC553E2,FALSE,has_even_y(R) is false
+7,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,1FA62E331EDBC21C394792D2AB1100A7B432B013DF3F6FF4F99FCB33E0E1515F28890B3EDB6E7189B630448B515CE4F8622A954CFE545735AAEA5134FCCDB2BD,FALSE,negated message
+8,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769961764B3AA9B2FFCB6EF947B6887A226E8D7C93E00C5ED0C1834FF0D0C2E6DA6,FALSE,negated s value
+9,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,0000000000000000000000000000000000000000000000000000000000000000123DDA8328AF9C23A94C1FEECFD123BA4FB73476F0D594DCB65C6425BD186051,FALSE,sG - eP is infinite. Test fails in single verification if has_even_y(inf) is defined as true and x(inf) as 0
+10,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,00000000000000000000000000000000000000000000000000000000000000017615FBAF5AE28864013C099742DEADB4DBA87F11AC6754F93780D5A1837CF197,FALSE,sG - eP is infinite. Test fails in single verification if has_even_y(inf) is defined as true and x(inf) as 1
+11,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,4A298DACAE57395A15D0795DDBFD1DCB564DA82B0F269BC70A74F8220429BA1D69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B,FALSE,sig[0:32] is not an X coordinate on the curve
+12,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F69E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B,FALSE,sig[0:32] is equal to field size
+13,,DFF1D77F2A671C5F36183726DB2341BE58FEAE1DA2DECED843240F7B502BA659,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E177769FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141,FALSE,sig[32:64] is equal to curve order
+14,,FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC30,,243F6A8885A308D313198A2E03707344A4093822299F31D0082EFA98EC4E6C89,6CFF5C3BA86C69EA4B7376F31A9BCB4F74C1976089B2D9963DA2E5543E17776969E89B4C5564D00349106B8497785DD7D1D713A8AE82B32FA79D5F7FC407D39B,FALSE,public key is not a valid X coordinate because it exceeds the field size
+15,0340034003400340034003400340034003400340034003400340034003400340,778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117,0000000000000000000000000000000000000000000000000000000000000000,,71535DB165ECD9FBBC046E5FFAEA61186BB6AD436732FCCC25291A55895464CF6069CE26BF03466228F19A3A62DB8A649F2D560FAC652827D1AF0574E427AB63,TRUE,message of size 0 (added 2022-12)
+16,0340034003400340034003400340034003400340034003400340034003400340,778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117,0000000000000000000000000000000000000000000000000000000000000000,11,08A20A0AFEF64124649232E0693C583AB1B9934AE63B4C3511F3AE1134C6A303EA3173BFEA6683BD101FA5AA5DBC1996FE7CACFC5A577D33EC14564CEC2BACBF,TRUE,message of size 1 (added 2022-12)
+17,0340034003400340034003400340034003400340034003400340034003400340,778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117,0000000000000000000000000000000000000000000000000000000000000000,0102030405060708090A0B0C0D0E0F1011,5130F39A4059B43BC7CAC09A19ECE52B5D8699D1A71E3C52DA9AFDB6B50AC370C4A482B77BF960F8681540E25B6771ECE1E5A37FD80E5A51897C5566A97EA5A5,TRUE,message of size 17 (added 2022-12)
+18,0340034003400340034003400340034003400340034003400340034003400340,778CAA53B4393AC467774D09497A87224BF9FAB6F6E68B23086497324D6FD117,0000000000000000000000000000000000000000000000000000000000000000,99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999,403B12B0D8555A344175EA7EC746566303321E5DBFA8BE6F091635163ECA79A8585ED3E3170807E7C03B720FC54C7B23897FCBA0E9D0B4A06894CFD249F22367,TRUE,message of size 100 (added 2022-12)";
+
+#[test]
+fn test_schnorr_bip340_vectors() {
+    let curve = secp256k1().get_curve();
+    for line in BIP340_VECTORS.lines().skip(1) {
+        let fields: Vec<&str> = line.splitn(8, ',').collect();
+        let index = fields[0];
+        let message = hex_to_bytes(fields[4]);
+        let signature = schnorr::Signature::from_bytes(&hex_to_bytes(fields[5])).unwrap();
+        let expected = fields[6] == "TRUE";
+        if !fields[1].is_empty() {
+            let key = PrivateKey::new(BigUint::from_bytes_be(&hex_to_bytes(fields[1])), curve);
+            let mut aux_rand = [0u8; 32];
+            aux_rand.copy_from_slice(&hex_to_bytes(fields[3]));
+            assert_eq!(
+                key.x_only_public_key().to_bytes(),
+                hex_to_bytes(fields[2]),
+                "vector {}",
+                index
+            );
+            assert_eq!(
+                key.sign_schnorr(&message, &aux_rand),
+                signature,
+                "vector {}",
+                index
+            );
+        }
+        // Invalid keys and out of range values fail as errors rather than Ok(false)
+        let verified = XOnlyPublicKey::from_bytes(&hex_to_bytes(fields[2]), curve)
+            .and_then(|key| signature.verify(&key, &message));
+        assert_eq!(verified == Ok(true), expected, "vector {}", index);
+    }
+}
+
+#[test]
+fn test_schnorr() {
+    let curve = secp256k1().get_curve();
+    let key = PrivateKey::new(8675309, curve);
+    let public_key = key.x_only_public_key();
+    assert!(!public_key.get_point().get_y().is_odd());
+    assert_eq!(
+        public_key.get_point().get_x(),
+        key.get_public_key().get_point().get_x()
+    );
+    let signature = key.sign_schnorr(b"taproot", &[7u8; 32]);
+    assert_eq!(signature.verify(&public_key, b"taproot"), Ok(true));
+    assert_eq!(signature.verify(&public_key, b"taproot!"), Ok(false));
+    // Fresh randomness gives a different but equally valid signature
+    let other = key.sign_schnorr(b"taproot", &[8u8; 32]);
+    assert_ne!(other, signature);
+    assert_eq!(other.verify(&public_key, b"taproot"), Ok(true));
+    let bytes = signature.to_bytes().unwrap();
+    assert_eq!(schnorr::Signature::from_bytes(&bytes), Ok(signature));
+
+    assert_eq!(
+        XOnlyPublicKey::from_bytes(&public_key.to_bytes()[1..], curve),
+        Err(Error::InvalidLength {
+            expected: 32,
+            found: 31
+        })
+    );
+    assert_eq!(
+        schnorr::Signature::from_bytes(&bytes[..63]),
+        Err(Error::InvalidLength {
+            expected: 64,
+            found: 63
+        })
+    );
+    assert_eq!(
+        schnorr::Signature::try_new(1, -1),
+        Err(Error::NegativeValue)
+    );
 }
@@ -0,0 +1,96 @@
+use crate::address::Address;
+use crate::curves::secp256k1;
+use crate::ecdsa::{recover_public_key, PrivateKey, Signature};
+use crate::encoding::{base64_decode, base64_encode, encode_varint};
+use crate::error::Error;
+use crate::hash::{hash256, MessageHash};
+
+const MAGIC: &[u8] = b"Bitcoin Signed Message:\n";
+
+/// The kind of address a signed message proves ownership of, which BIP 137 records in the
+/// signature's header byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressType {
+    P2pkhUncompressed,
+    P2pkh,
+    P2wpkh,
+}
+
+impl AddressType {
+    // Headers run from 27 to 42 in groups of four, one per recovery id
+    fn header_base(&self) -> u8 {
+        match self {
+            AddressType::P2pkhUncompressed => 27,
+            AddressType::P2pkh => 31,
+            AddressType::P2wpkh => 39,
+        }
+    }
+}
+
+/// Returns the hash signed for a message: hash256 of the magic prefix and the message, each
+/// preceded by its CompactSize length. The prefix keeps a signed message from ever doubling as a
+/// transaction signature.
+pub fn message_hash(message: &[u8]) -> MessageHash {
+    let mut data = encode_varint(MAGIC.len() as u64);
+    data.extend_from_slice(MAGIC);
+    data.extend(encode_varint(message.len() as u64));
+    data.extend_from_slice(message);
+    MessageHash::new(hash256(&data))
+}
+
+/// Signs a message with a secp256k1 key, returning the 65-byte recoverable signature (header
+/// byte, r and s) in base64, as produced by Bitcoin Core's signmessage. Fails if the key belongs
+/// to any other curve or group.
+pub fn sign_message(
+    key: &PrivateKey,
+    message: &[u8],
+    address_type: AddressType,
+) -> Result<String, Error> {
+    let secp = secp256k1();
+    let curve = key.get_public_key().get_point().get_curve();
+    if curve != secp.get_curve() || curve.get_generator() != Some(secp.get_generator()) {
+        return Err(Error::MismatchedCurves);
+    }
+    let (signature, recid) = key.sign_recoverable(&message_hash(message));
+    let mut bytes = vec![address_type.header_base() + recid];
+    bytes.extend_from_slice(&signature.to_compact()?);
+    Ok(base64_encode(&bytes))
+}
+
+/// Verifies a base64 signed message against a mainnet P2PKH or P2WPKH address by recovering the
+/// signing key and comparing its address. Malformed addresses and signatures are reported as
+/// errors, while well-formed signatures from another key return Ok(false).
+pub fn verify_message(address: &str, signature: &str, message: &[u8]) -> Result<bool, Error> {
+    let address: Address = address.parse()?;
+    let bytes = base64_decode(signature)?;
+    if bytes.len() != 65 {
+        return Err(Error::InvalidLength {
+            expected: 65,
+            found: bytes.len(),
+        });
+    }
+    let header = bytes[0];
+    if !(27..=42).contains(&header) {
+        return Err(Error::InvalidSignatureHeader(header));
+    }
+    let recid = (header - 27) % 4;
+    let compressed = header >= 31;
+    let signature = Signature::from_compact(&bytes[1..])?;
+    let z = message_hash(message);
+    // A well-formed signature may still fail to yield a key, for example if r lifts to no point
+    let key = match recover_public_key(&signature, recid, &z, secp256k1().get_curve()) {
+        Ok(key) => key,
+        Err(Error::PointNotOnCurve) | Err(Error::PointAtInfinity) => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    // The header also names the address type, which must match the address. Headers 35 to 38 are
+    // for P2SH-P2WPKH, which neither address type is.
+    let derived = match address {
+        Address::P2pkh(_) if header <= 34 => Address::p2pkh(&key, compressed),
+        // Segwit keys are always compressed. Electrum signs for them with the compressed P2PKH
+        // headers, so those are accepted alongside the BIP 137 segwit headers.
+        Address::P2wpkh(_) if compressed && !(35..=38).contains(&header) => Address::p2wpkh(&key),
+        _ => return Ok(false),
+    };
+    Ok(derived == address)
+}
@@ -0,0 +1,97 @@
+use crate::hash::hmac_sha256;
+use num_bigint::{BigUint, ToBigUint};
+
+/// Deterministic nonce generation for (EC)DSA as specified in RFC 6979, section 3.2, using
+/// HMAC-SHA256. Iterating yields the sequence of candidate nonces in [1, q) for a given secret
+/// and message hash, so a signer that hits a degenerate signature can simply take the next one.
+pub struct NonceGenerator {
+    k: [u8; 32],
+    v: [u8; 32],
+    order: BigUint,
+    first: bool,
+}
+
+impl NonceGenerator {
+    /// Creates a generator for the secret x and message hash h1 over a group of order q
+    pub fn new(secret: &BigUint, h1: &[u8], q: &BigUint) -> NonceGenerator {
+        let rlen = q.bits().div_ceil(8);
+        let x = int2octets(secret, rlen);
+        let h1 = bits2octets(h1, q);
+
+        let mut generator = NonceGenerator {
+            k: [0x00; 32],
+            v: [0x01; 32],
+            order: q.clone(),
+            first: true,
+        };
+        // Steps d. through g.
+        for &separator in [0x00, 0x01].iter() {
+            let mut data = generator.v.to_vec();
+            data.push(separator);
+            data.extend_from_slice(&x);
+            data.extend_from_slice(&h1);
+            generator.k = hmac_sha256(&generator.k, &data);
+            generator.v = hmac_sha256(&generator.k, &generator.v);
+        }
+        generator
+    }
+}
+
+impl Iterator for NonceGenerator {
+    type Item = BigUint;
+    fn next(&mut self) -> Option<BigUint> {
+        let qlen = self.order.bits();
+        loop {
+            // Step h.3: when a candidate is rejected (or the caller asks for another one) the
+            // state is reseeded before generating the next candidate
+            if !self.first {
+                let mut data = self.v.to_vec();
+                data.push(0x00);
+                self.k = hmac_sha256(&self.k, &data);
+                self.v = hmac_sha256(&self.k, &self.v);
+            }
+            self.first = false;
+
+            // Steps h.1 and h.2: concatenate HMAC outputs until we have at least qlen bits
+            let mut t = Vec::new();
+            while t.len() * 8 < qlen {
+                self.v = hmac_sha256(&self.k, &self.v);
+                t.extend_from_slice(&self.v);
+            }
+            let k = bits2int(&t, qlen);
+            if k >= 1.to_biguint().unwrap() && k < self.order {
+                return Some(k);
+            }
+        }
+    }
+}
+
+/// Returns the first RFC 6979 nonce for the secret x and message hash h1 over a group of order q
+pub fn generate_k(secret: &BigUint, h1: &[u8], q: &BigUint) -> BigUint {
+    NonceGenerator::new(secret, h1, q).next().unwrap()
+}
+
+// Interprets the leftmost qlen bits of a byte string as a big-endian integer (section 2.3.2)
+fn bits2int(bytes: &[u8], qlen: usize) -> BigUint {
+    let value = BigUint::from_bytes_be(bytes);
+    let blen = bytes.len() * 8;
+    if blen > qlen {
+        value >> (blen - qlen)
+    } else {
+        value
+    }
+}
+
+// Encodes an integer as exactly rlen big-endian bytes (section 2.3.3)
+fn int2octets(value: &BigUint, rlen: usize) -> Vec<u8> {
+    let bytes = value.to_bytes_be();
+    let mut octets = vec![0u8; rlen.saturating_sub(bytes.len())];
+    octets.extend_from_slice(&bytes[bytes.len().saturating_sub(rlen)..]);
+    octets
+}
+
+// Converts a hash to an integer mod q, then back to rlen bytes (section 2.3.4)
+fn bits2octets(bytes: &[u8], q: &BigUint) -> Vec<u8> {
+    let z = bits2int(bytes, q.bits()) % q;
+    int2octets(&z, q.bits().div_ceil(8))
+}
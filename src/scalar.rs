@@ -0,0 +1,164 @@
+use crate::encoding::to_fixed_bytes;
+use crate::error::Error;
+use crate::finite_field::FieldElement;
+use num_bigint::{BigUint, ToBigUint};
+use std::fmt;
+
+/// An integer modulo the group order n, such as a private key, a nonce or a signature's r and s.
+///
+/// Scalars only combine with scalars of the same order, so they can't be mixed up with field
+/// elements, whose modulus is the curve's prime p rather than n. Values are always reduced below n.
+#[derive(Debug, Clone, Eq)]
+pub struct Scalar {
+    value: BigUint,
+    order: BigUint,
+}
+
+impl Scalar {
+    /// Creates value (mod n). Panics if value is negative or n is less than 2.
+    pub fn new<T>(value: T, order: &BigUint) -> Scalar
+    where
+        T: ToBigUint,
+    {
+        Scalar::try_new(value, order).unwrap_or_else(|e| panic!("{}", e))
+    }
+    pub fn try_new<T>(value: T, order: &BigUint) -> Result<Scalar, Error>
+    where
+        T: ToBigUint,
+    {
+        let value = value.to_biguint().ok_or(Error::NegativeValue)?;
+        if order < &2.to_biguint().unwrap() {
+            return Err(Error::InvalidModulus);
+        }
+        Ok(Scalar {
+            value: value % order,
+            order: order.clone(),
+        })
+    }
+    /// Reads bytes as a big-endian integer and reduces it mod n, as when turning a hash into a
+    /// scalar
+    pub fn from_bytes_reduced(bytes: &[u8], order: &BigUint) -> Scalar {
+        Scalar::new(BigUint::from_bytes_be(bytes), order)
+    }
+    /// Serializes the value as a big-endian integer, padded to the byte length of n
+    pub fn to_bytes(&self) -> Vec<u8> {
+        to_fixed_bytes(&self.value, self.order.bits().div_ceil(8)).unwrap()
+    }
+    pub fn get_value(&self) -> &BigUint {
+        &self.value
+    }
+    pub fn get_order(&self) -> &BigUint {
+        &self.order
+    }
+    pub fn is_zero(&self) -> bool {
+        self.value == 0.to_biguint().unwrap()
+    }
+    /// Returns the multiplicative inverse, or None for zero. Variable time, so only for public
+    /// values such as a signature's s.
+    pub fn invert(&self) -> Option<Scalar> {
+        self.as_field_element()
+            .invert()
+            .map(|inverse| self.with_value(inverse.get_value().clone()))
+    }
+    /// Like invert, but without branching on the value, for secrets such as signing nonces
+    pub fn invert_constant_time(&self) -> Option<Scalar> {
+        self.as_field_element()
+            .invert_constant_time()
+            .map(|inverse| self.with_value(inverse.get_value().clone()))
+    }
+    /// Compares two scalars by looking at every byte, instead of stopping at the first difference
+    pub fn ct_eq(&self, other: &Scalar) -> bool {
+        let difference = self
+            .to_bytes()
+            .iter()
+            .zip(other.to_bytes().iter())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+        self.order == other.order && difference == 0
+    }
+    // The group order is prime, so inversion mod n is inversion in the field F_n
+    fn as_field_element(&self) -> FieldElement {
+        FieldElement::new(self.value.clone(), self.order.clone())
+    }
+    fn with_value(&self, value: BigUint) -> Scalar {
+        Scalar {
+            value: value % &self.order,
+            order: self.order.clone(),
+        }
+    }
+    fn check_order(&self, other: &Scalar) {
+        if self.order != other.order {
+            panic!("{}", Error::MismatchedOrders);
+        }
+    }
+}
+
+impl PartialEq for Scalar {
+    fn eq(&self, other: &Scalar) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl std::ops::Add for Scalar {
+    type Output = Scalar;
+    fn add(self, other: Scalar) -> Scalar {
+        &self + &other
+    }
+}
+
+impl std::ops::Add for &Scalar {
+    type Output = Scalar;
+    fn add(self, other: &Scalar) -> Scalar {
+        self.check_order(other);
+        self.with_value(&self.value + &other.value)
+    }
+}
+
+impl std::ops::Sub for Scalar {
+    type Output = Scalar;
+    fn sub(self, other: Scalar) -> Scalar {
+        &self - &other
+    }
+}
+
+impl std::ops::Sub for &Scalar {
+    type Output = Scalar;
+    fn sub(self, other: &Scalar) -> Scalar {
+        self.check_order(other);
+        self.with_value(&self.value + (&self.order - &other.value))
+    }
+}
+
+impl std::ops::Mul for Scalar {
+    type Output = Scalar;
+    fn mul(self, other: Scalar) -> Scalar {
+        &self * &other
+    }
+}
+
+impl std::ops::Mul for &Scalar {
+    type Output = Scalar;
+    fn mul(self, other: &Scalar) -> Scalar {
+        self.check_order(other);
+        self.with_value(&self.value * &other.value)
+    }
+}
+
+impl std::ops::Neg for Scalar {
+    type Output = Scalar;
+    fn neg(self) -> Scalar {
+        -&self
+    }
+}
+
+impl std::ops::Neg for &Scalar {
+    type Output = Scalar;
+    fn neg(self) -> Scalar {
+        self.with_value(&self.order - &self.value)
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:x}", self.value)
+    }
+}
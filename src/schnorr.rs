@@ -0,0 +1,180 @@
+use crate::ecdsa::{self, PrivateKey};
+use crate::elliptic_curve::{CurvePoint, EllipticCurve};
+use crate::error::Error;
+use crate::field::Field;
+use crate::finite_field::FieldElement;
+use crate::hash::tagged_hash;
+use crate::scalar::Scalar;
+use num_bigint::{BigUint, ToBigUint};
+use std::fmt;
+
+/// A BIP 340 Schnorr signature: the x-coordinate of the nonce point R, whose y is even, and
+/// s = k + e * d (mod N). The pair is stored as an ECDSA signature, which shares its validation
+/// and 64-byte encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    inner: ecdsa::Signature,
+}
+
+/// A public key given by its x-coordinate alone, standing for the point with that x and an even y.
+/// Taproot outputs and BIP 340 signatures commit to keys in this 32-byte form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XOnlyPublicKey<F = FieldElement> {
+    point: CurvePoint<F>,
+}
+
+impl Signature {
+    /// Creates a signature from r and s. Panics if either is negative.
+    pub fn new<T, U>(r: T, s: U) -> Signature
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        Signature::try_new(r, s).unwrap_or_else(|e| panic!("{}", e))
+    }
+    /// Creates a signature from r and s, failing if either is negative
+    pub fn try_new<T, U>(r: T, s: U) -> Result<Signature, Error>
+    where
+        T: ToBigUint,
+        U: ToBigUint,
+    {
+        ecdsa::Signature::try_new(r, s).map(|inner| Signature { inner })
+    }
+    /// Parses the 64-byte encoding: r and s as 32-byte big-endian integers. Their ranges are only
+    /// checked when verifying.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Signature, Error> {
+        ecdsa::Signature::from_compact(bytes).map(|inner| Signature { inner })
+    }
+    /// Serializes to the 64-byte encoding. Fails if r or s does not fit in 32 bytes.
+    pub fn to_bytes(&self) -> Result<[u8; 64], Error> {
+        self.inner.to_compact()
+    }
+    pub fn get_r(&self) -> &BigUint {
+        self.inner.get_r()
+    }
+    pub fn get_s(&self) -> &BigUint {
+        self.inner.get_s()
+    }
+    /// Verifies this signature over a message against an x-only public key. An s outside [0, N) is
+    /// reported as an error, while signatures that don't match return Ok(false), including those
+    /// whose r is not the x-coordinate of any point.
+    pub fn verify<F: Field>(&self, key: &XOnlyPublicKey<F>, message: &[u8]) -> Result<bool, Error> {
+        let curve = key.point.get_curve();
+        let generator = curve.get_generator().ok_or(Error::MissingGroup)?;
+        let n = curve.get_order().unwrap();
+        if self.get_s() >= n {
+            return Err(Error::ScalarOutOfRange);
+        }
+        // Reducing r mod p could only make it match a point it doesn't encode
+        let r = key.point.get_x().element_from_integer(self.get_r());
+        if &r.to_integer() != self.get_r() {
+            return Ok(false);
+        }
+        let e = challenge(&r.to_bytes(), &key.to_bytes(), message, n);
+        // s * G = R + e * P, so s * G - e * P gives back R
+        let point = curve.multi_scalar_mul(&[
+            (&Scalar::new(self.get_s().clone(), n), &generator),
+            (&-e, &key.point),
+        ]);
+        Ok(!point.is_infinity() && !point.get_y().is_odd() && point.get_x() == &r)
+    }
+}
+
+impl<F: Field> XOnlyPublicKey<F> {
+    /// Parses a 32-byte x-coordinate, lifting it to the point with even y. Fails if x is not below
+    /// the curve's prime or no point has that x-coordinate.
+    pub fn from_bytes(bytes: &[u8], curve: &EllipticCurve<F>) -> Result<XOnlyPublicKey<F>, Error> {
+        // Lengths are checked on the SEC encoding, which has one more byte for the prefix
+        let point = lift_x(bytes, curve).map_err(|e| match e {
+            Error::InvalidLength { expected, found } => Error::InvalidLength {
+                expected: expected - 1,
+                found: found - 1,
+            },
+            e => e,
+        })?;
+        Ok(XOnlyPublicKey { point })
+    }
+    /// Serializes the key as its big-endian x-coordinate
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.point.get_x().to_bytes()
+    }
+    pub fn get_point(&self) -> &CurvePoint<F> {
+        &self.point
+    }
+}
+
+impl<F: Field> PrivateKey<F> {
+    /// Returns the x-only form of the public key, for use with BIP 340 signatures
+    pub fn x_only_public_key(&self) -> XOnlyPublicKey<F> {
+        let point = self.get_public_key().get_point();
+        XOnlyPublicKey {
+            point: lift_x(&point.get_x().to_bytes(), point.get_curve()).unwrap(),
+        }
+    }
+    /// Signs a message of any length following BIP 340. The nonce is derived from the secret, the
+    /// message and 32 bytes of auxiliary randomness, which should be fresh for each signature but
+    /// may be all zeros when no randomness is available.
+    pub fn sign_schnorr(&self, message: &[u8], aux_rand: &[u8; 32]) -> Signature {
+        let point = self.get_public_key().get_point();
+        let curve = point.get_curve();
+        let n = curve.get_order().unwrap();
+        // The x-only key stands for whichever of P and -P has an even y, so sign with the matching
+        // one of d and -d
+        let d = if point.get_y().is_odd() {
+            -self.get_secret()
+        } else {
+            self.get_secret().clone()
+        };
+        let key_bytes = point.get_x().to_bytes();
+        // Masking the secret with hashed randomness before hashing it into the nonce hides it from
+        // side channels in the hash function
+        let masked: Vec<u8> = d
+            .to_bytes()
+            .iter()
+            .zip(tagged_hash("BIP0340/aux", aux_rand).iter())
+            .map(|(a, b)| a ^ b)
+            .collect();
+        let nonce = tagged_hash(
+            "BIP0340/nonce",
+            &[&masked[..], &key_bytes, message].concat(),
+        );
+        let k = Scalar::from_bytes_reduced(&nonce, n);
+        // Only a hash equal to 0 or N gives a zero nonce, which no one can find
+        if k.is_zero() {
+            panic!("{}", Error::ScalarOutOfRange);
+        }
+        let nonce_point = curve.mul_generator(&k).unwrap();
+        let k = if nonce_point.get_y().is_odd() { -k } else { k };
+        let r = nonce_point.get_x();
+        let e = challenge(&r.to_bytes(), &key_bytes, message, n);
+        Signature::new(r.to_integer(), (k + e * d).get_value().clone())
+    }
+}
+
+// Returns the point with x-coordinate x and an even y, by decompressing it with the 0x02 prefix
+fn lift_x<F: Field>(x: &[u8], curve: &EllipticCurve<F>) -> Result<CurvePoint<F>, Error> {
+    let mut sec = vec![0x02];
+    sec.extend_from_slice(x);
+    CurvePoint::from_sec(&sec, curve)
+}
+
+// The challenge e = hash(R || P || m) mod N, binding the signature to the nonce, key and message
+fn challenge(r: &[u8], key: &[u8], message: &[u8], order: &BigUint) -> Scalar {
+    let hash = tagged_hash("BIP0340/challenge", &[r, key, message].concat());
+    Scalar::from_bytes_reduced(&hash, order)
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl<F: Field> fmt::Display for XOnlyPublicKey<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.to_bytes().iter() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}